@@ -0,0 +1,472 @@
+// SPDX-License-Identifier: MIT
+//! Analyses performed on the content of a `SignalDB`.
+mod assertion;
+mod changes;
+mod coverage;
+mod fsm;
+mod latency;
+mod memory;
+mod pulse;
+mod regfile;
+mod regmap;
+mod riscv;
+mod saif;
+mod sample;
+mod timing;
+mod unknown;
+mod values;
+
+pub use self::assertion::{
+    Assertion, AssertionKind, AssertionReport, AssertionViolation, REPORTED_VIOLATIONS,
+};
+pub use self::changes::{ChangeLog, ValueChange};
+pub use self::coverage::{BitToggles, CoverageReport, ScopeCoverage, SignalCoverage};
+pub use self::fsm::{FsmReport, State, StateNames, Transition};
+pub use self::latency::{LatencyReport, Pairing, parse_latency};
+pub use self::memory::{Memory, MemoryWrite, WORDS_PER_LINE, WritePort, parse_address};
+pub use self::pulse::{PulseCheck, PulseReport, Violation, ViolationKind};
+pub use self::regfile::{REGISTER_COUNT, shadow_registers};
+pub use self::regmap::{BusAccess, BusPort, BusTrace, Field, Register, RegisterMap};
+pub use self::riscv::{InstructionTrace, TracedInstruction, Xlen, disassemble};
+pub use self::saif::{BitActivity, SwitchingActivity};
+pub use self::sample::{CycleTable, Radix};
+pub use self::timing::{SetupHoldCheck, TimingReport, TimingViolation, TimingViolationKind};
+pub use self::unknown::{Episode, UnknownReport, UnknownTrace};
+pub use self::values::{ValueReport, ValueStats, ValueTime};
+
+use crate::search::parser;
+use crate::signaldb::{Signal, SignalDB, SignalNotFound, SignalValue, TimeDescr, Timestamp};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+/// Error reported when the parameters of an analysis are invalid
+#[derive(Debug)]
+pub struct AnalysisError {
+    msg: String,
+}
+
+impl AnalysisError {
+    pub(crate) fn new(msg: &str) -> AnalysisError {
+        AnalysisError {
+            msg: msg.to_string(),
+        }
+    }
+}
+
+impl Error for AnalysisError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid analysis: {}", self.msg)
+    }
+}
+
+/// Length of a time period
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Duration {
+    /// Length expressed as a time
+    Time(Timestamp),
+    /// Length expressed as a number of clock cycles
+    Cycles(usize),
+}
+
+impl Duration {
+    /// Parse a `Duration` such as `42` or `10 cycles`. Bare integers are expressed in the time
+    /// scale of the `SignalDB`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::Duration;
+    /// use dwfv::signaldb::{Scale, SignalDB, Timestamp};
+    /// let db = SignalDB::new();
+    ///
+    /// assert_eq!(
+    ///     Duration::parse(&db, "42").unwrap(),
+    ///     Duration::Time(Timestamp::new(42, Scale::Picosecond))
+    /// );
+    /// assert_eq!(Duration::parse(&db, "10 cycles").unwrap(), Duration::Cycles(10));
+    /// assert!(Duration::parse(&db, "foo").is_err());
+    /// ```
+    pub fn parse(signaldb: &SignalDB, s: &str) -> Result<Duration, AnalysisError> {
+        let s = s.trim();
+        for suffix in ["cycles", "cycle"] {
+            if let Some(cycles) = s.strip_suffix(suffix) {
+                return cycles
+                    .trim()
+                    .parse()
+                    .map(Duration::Cycles)
+                    .map_err(|_| AnalysisError::new(&format!("invalid duration '{}'", s)));
+            }
+        }
+        parse_time(signaldb, s).map(Duration::Time)
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Duration::Time(t) => write!(f, "{}", t),
+            Duration::Cycles(1) => write!(f, "1 cycle"),
+            Duration::Cycles(n) => write!(f, "{} cycles", n),
+        }
+    }
+}
+
+impl PartialOrd for Duration {
+    fn partial_cmp(&self, other: &Duration) -> Option<std::cmp::Ordering> {
+        match (self, other) {
+            (Duration::Time(a), Duration::Time(b)) => a.partial_cmp(b),
+            (Duration::Cycles(a), Duration::Cycles(b)) => a.partial_cmp(b),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a time, either with a unit (e.g. `10ns` or `1.5us`) or as a number of ticks of the time
+/// scale of the `SignalDB`.
+///
+/// # Example
+///
+/// ```
+/// use dwfv::analysis::parse_time;
+/// use dwfv::signaldb::{Scale, SignalDB, Timestamp};
+/// let db = SignalDB::new();
+/// assert_eq!(parse_time(&db, "42").unwrap(), Timestamp::new(42, Scale::Picosecond));
+/// assert_eq!(parse_time(&db, "1.5us").unwrap(), Timestamp::new(1500, Scale::Nanosecond));
+/// assert!(parse_time(&db, "1.5").is_err());
+/// ```
+pub fn parse_time(signaldb: &SignalDB, s: &str) -> Result<Timestamp, AnalysisError> {
+    if let Ok(value) = s.trim().parse::<i64>() {
+        return Ok(signaldb.get_timescale() * value);
+    }
+    s.parse()
+        .map_err(|_| AnalysisError::new(&format!("invalid time '{}'", s)))
+}
+
+/// Parse the beginning and the end of a time window, both being optional, and check that the
+/// window does not end before it begins.
+///
+/// # Example
+///
+/// ```
+/// use dwfv::analysis::parse_window;
+/// use dwfv::signaldb::{Scale, SignalDB, Timestamp};
+/// let db = SignalDB::new();
+/// assert_eq!(
+///     parse_window(&db, Some("10"), None).unwrap(),
+///     (Some(Timestamp::new(10, Scale::Picosecond)), None)
+/// );
+/// assert!(parse_window(&db, Some("1ns"), Some("100")).is_err());
+/// ```
+pub fn parse_window(
+    signaldb: &SignalDB,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<(Option<Timestamp>, Option<Timestamp>), AnalysisError> {
+    let begin = from.map(|t| parse_time(signaldb, t)).transpose()?;
+    let end = to.map(|t| parse_time(signaldb, t)).transpose()?;
+    if let (Some(begin), Some(end)) = (begin, end) {
+        check_window(begin, end)?
+    }
+    Ok((begin, end))
+}
+
+/// Check that a time window does not end before it begins.
+pub(crate) fn check_window(begin: Timestamp, end: Timestamp) -> Result<(), AnalysisError> {
+    if end < begin {
+        return Err(AnalysisError::new(&format!(
+            "the window ends at {} before it begins at {}",
+            end, begin
+        )));
+    }
+    Ok(())
+}
+
+/// Parse a literal value (e.g. `42`, `h2a` or `b101010`).
+pub(crate) fn parse_value(s: &str) -> Result<SignalValue, AnalysisError> {
+    parser::literal(s).ok_or_else(|| AnalysisError::new(&format!("invalid value '{}'", s)))
+}
+
+/// Quote and escape a string to be included in a JSON document.
+pub(crate) fn json_string(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quote a field of a CSV file if needed.
+pub(crate) fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Check if a name matches a glob pattern, where `*` matches any sequence of characters and `?`
+/// matches a single character.
+///
+/// # Example
+///
+/// ```
+/// use dwfv::analysis::glob_match;
+/// assert!(glob_match("top.*.clk", "top.c1.clk"));
+/// assert!(glob_match("dat?", "data"));
+/// assert!(!glob_match("dat?", "data0"));
+/// ```
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1
+            }
+            _ => match backtrack {
+                Some((bp, bn)) => {
+                    p = bp + 1;
+                    n = bn + 1;
+                    backtrack = Some((bp, bn + 1))
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+fn hierarchical_name(signal: &Signal) -> String {
+    let mut path = signal.path.iter().skip(1).cloned().collect::<Vec<_>>();
+    path.push(signal.name.clone());
+    path.join(".")
+}
+
+/// Find the signals whose identifier, name or hierarchical name (e.g. `top.cpu.clk`) matches one
+/// of the comma-separated glob patterns. All the signals are returned if no pattern is given.
+pub(crate) fn select_signals(signaldb: &SignalDB, patterns: Option<&str>) -> Vec<String> {
+    signaldb.find_signals(|signal| match patterns {
+        Some(patterns) => patterns.split(',').map(str::trim).any(|pattern| {
+            glob_match(pattern, &signal.id)
+                || glob_match(pattern, &signal.name)
+                || glob_match(pattern, &hierarchical_name(signal))
+        }),
+        None => true,
+    })
+}
+
+/// Find the identifier of a signal referenced by its identifier, name or hierarchical name.
+pub(crate) fn resolve_signal(
+    signals: &BTreeMap<String, Signal>,
+    name: &str,
+) -> Result<String, SignalNotFound> {
+    if signals.contains_key(name) {
+        return Ok(name.to_string());
+    }
+    signals
+        .values()
+        .find(|signal| signal.name == name || hierarchical_name(signal) == name)
+        .map(|signal| signal.id.clone())
+        .ok_or_else(|| SignalNotFound::new(name))
+}
+
+/// Check whether a layout command of the TUI refers to an analysis.
+pub fn is_command(command: &str) -> bool {
+    matches!(
+        command,
+        "glitch" | "min-pulse" | "max-pulse" | "setup-hold" | "latency" | "unknowns"
+    )
+}
+
+/// Run the analysis described by a layout command of the TUI and return its findings.
+///
+/// Supported commands:
+///
+/// - `glitch THRESHOLD [GLOB]`: pulses shorter than `THRESHOLD` and simultaneous transitions
+/// - `min-pulse SIGNAL[=VALUE]:WIDTH [CLOCK]`: pulses shorter than `WIDTH`
+/// - `max-pulse SIGNAL[=VALUE]:WIDTH [CLOCK]`: pulses longer than `WIDTH`
+/// - `setup-hold DATA:SETUP:HOLD CLOCK`: changes of `DATA` too close to the rising edges of `CLOCK`
+/// - `latency START => END [by ID_SIGNAL]`: periods between the findings of two search
+///   expressions, paired in FIFO order or by the value of an identifier signal (see
+///   `Pairing::parse_id`)
+/// - `unknowns [GLOB]`: periods during which the signals hold unknown values after their
+///   initialization
+///
+/// # Example
+///
+/// ```
+/// use dwfv::analysis;
+/// use dwfv::signaldb::{Scale, SignalDB, TimeDescr, Timestamp};
+/// let vcd = std::io::Cursor::new("$scope module top $end
+/// $var wire 1 0 foo $end
+/// $upscope $end
+/// $enddefinitions $end
+/// #0
+/// 00
+/// #10
+/// 10
+/// #11
+/// 00
+/// #30
+/// 10
+/// ");
+///
+/// let db = SignalDB::from_vcd(vcd).unwrap();
+/// let findings = analysis::run_command(&db, "glitch", "2").unwrap();
+/// assert_eq!(findings.len(), 1);
+/// ```
+pub fn run_command(
+    signaldb: &SignalDB,
+    command: &str,
+    args: &str,
+) -> Result<Vec<TimeDescr>, Box<dyn Error>> {
+    if command == "latency" {
+        let (start, end) = parse_latency(args)?;
+        let (end, pairing) = match end.rsplit_once(" by ") {
+            Some((end, id)) if !id.trim().contains(char::is_whitespace) => {
+                (end.trim(), Pairing::parse_id(id))
+            }
+            _ => (end, Pairing::Fifo),
+        };
+        return Ok(LatencyReport::measure(signaldb, start, end, &pairing)?.findings());
+    }
+
+    let args: Vec<&str> = args.split_whitespace().collect();
+    let findings = match (command, &args[..]) {
+        ("glitch", [threshold, pattern @ ..]) if pattern.len() <= 1 => PulseReport::glitches(
+            signaldb,
+            pattern.first().copied(),
+            parse_time(signaldb, threshold)?,
+        )
+        .findings(),
+        ("min-pulse", [spec, clock @ ..]) | ("max-pulse", [spec, clock @ ..])
+            if clock.len() <= 1 =>
+        {
+            let check = if command == "min-pulse" {
+                PulseCheck::parse_min(signaldb, spec)?
+            } else {
+                PulseCheck::parse_max(signaldb, spec)?
+            };
+            PulseReport::check(signaldb, &[check], clock.first().copied())?.findings()
+        }
+        ("unknowns", pattern) if pattern.len() <= 1 => {
+            UnknownReport::trace(signaldb, pattern.first().copied(), None, None).findings()
+        }
+        ("setup-hold", [spec, clock]) => {
+            let check = SetupHoldCheck::parse(signaldb, spec)?;
+            TimingReport::check(signaldb, &[check], clock)?.findings()
+        }
+        _ => {
+            return Err(Box::new(AnalysisError::new(&format!(
+                "invalid arguments for '{}'",
+                command
+            ))));
+        }
+    };
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "foo"));
+        assert!(glob_match("f*o", "foo"));
+        assert!(glob_match("f*o", "fooooo"));
+        assert!(glob_match("*.clk", "top.cpu.clk"));
+        assert!(!glob_match("*.clk", "top.cpu.clk_en"));
+        assert!(!glob_match("foo", "fo"));
+        assert!(!glob_match("?", ""));
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(json_string("foo"), "\"foo\"");
+        assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\u000a\"");
+    }
+
+    #[test]
+    fn latency_command() {
+        let vcd = std::io::Cursor::new(
+            "$scope module top $end
+$var wire 1 0 req $end
+$var wire 1 1 ack $end
+$var wire 2 2 id $end
+$upscope $end
+$enddefinitions $end
+#0
+00
+01
+b0 2
+#10
+10
+b1 2
+#11
+00
+#20
+10
+b10 2
+#21
+00
+#30
+11
+#31
+01
+#40
+11
+b1 2
+#41
+01
+",
+        );
+        let db = SignalDB::from_vcd(vcd).unwrap();
+        let t = |value| Timestamp::new(value, crate::signaldb::Scale::Picosecond);
+
+        assert_eq!(
+            run_command(&db, "latency", "$0 <- 1 => $1 <- 1").unwrap(),
+            vec![
+                TimeDescr::Period(t(10), t(30)),
+                TimeDescr::Period(t(20), t(40))
+            ]
+        );
+        assert_eq!(
+            run_command(&db, "latency", "$0 <- 1 => $1 <- 1 by id").unwrap(),
+            vec![
+                TimeDescr::Period(t(20), t(30)),
+                TimeDescr::Period(t(10), t(40))
+            ]
+        );
+    }
+
+    #[test]
+    fn csv() {
+        assert_eq!(csv_field("foo"), "foo");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("a\"b"), "\"a\"\"b\"");
+    }
+}
@@ -0,0 +1,278 @@
+// SPDX-License-Identifier: MIT
+use super::AnalysisError;
+use crate::search::{ExprAst, Search};
+use crate::signaldb::{SignalDB, SignalValue, TimeDescr};
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Maximum number of violations reported per assertion
+pub const REPORTED_VIOLATIONS: usize = 10;
+
+/// Kind of assertion
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AssertionKind {
+    /// The expression must never be true
+    Never,
+    /// The expression must be true during the whole dump
+    Always,
+}
+
+/// Search expression checked on the whole dump
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assertion {
+    kind: AssertionKind,
+    expr: String,
+    ast: ExprAst,
+}
+
+/// Point or period of time at which an assertion does not hold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertionViolation {
+    /// Time of the violation
+    pub period: TimeDescr,
+    /// Identifiers of the signals of the expression, with their values at the beginning of the
+    /// violation
+    pub values: Vec<(String, SignalValue)>,
+}
+
+/// Result of assertion checks
+#[derive(Debug)]
+pub struct AssertionReport {
+    results: Vec<(Assertion, Vec<AssertionViolation>)>,
+}
+
+impl Assertion {
+    /// Create an assertion. A constraint on the length of the findings (`for ...`) is only
+    /// allowed in `never` assertions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{Assertion, AssertionKind};
+    /// let assertion = Assertion::new(AssertionKind::Never, "$err = 1").unwrap();
+    /// assert_eq!(assertion.to_string(), "never $err = 1");
+    /// assert!(Assertion::new(AssertionKind::Always, "$err =").is_err());
+    /// assert!(Assertion::new(AssertionKind::Never, "$busy = 1 for > 10").is_ok());
+    /// assert!(Assertion::new(AssertionKind::Always, "$busy = 0 for < 10").is_err());
+    /// ```
+    pub fn new(kind: AssertionKind, expr: &str) -> Result<Assertion, Box<dyn Error>> {
+        let ast = expr.parse::<ExprAst>()?;
+        if let (AssertionKind::Always, ExprAst::For(..)) = (kind, &ast) {
+            return Err(Box::new(AnalysisError::new(&format!(
+                "`always` assertions cannot constrain the length of the findings, got '{}'",
+                expr.trim()
+            ))));
+        }
+        Ok(Assertion {
+            kind,
+            expr: expr.trim().to_string(),
+            ast,
+        })
+    }
+
+    /// Parse a checks file, containing one assertion per line with the format `never EXPR` or
+    /// `always EXPR`. Empty lines and lines starting with `#` are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{Assertion, AssertionKind};
+    /// let assertions = Assertion::parse_checks("# Bus checks
+    /// never $err = 1
+    ///
+    /// always $rst = 0 or $ready = 1
+    /// ").unwrap();
+    /// assert_eq!(
+    ///     assertions,
+    ///     vec![
+    ///         Assertion::new(AssertionKind::Never, "$err = 1").unwrap(),
+    ///         Assertion::new(AssertionKind::Always, "$rst = 0 or $ready = 1").unwrap(),
+    ///     ]
+    /// );
+    /// assert!(Assertion::parse_checks("sometimes $err = 1").is_err());
+    /// assert!(Assertion::parse_checks("never $err =").is_err());
+    /// ```
+    pub fn parse_checks(checks: &str) -> Result<Vec<Assertion>, Box<dyn Error>> {
+        let mut assertions = Vec::new();
+        for (i, line) in checks.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (kind, expr) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let kind = match kind {
+                "never" => AssertionKind::Never,
+                "always" => AssertionKind::Always,
+                _ => {
+                    return Err(Box::new(AnalysisError::new(&format!(
+                        "line {}: expected `never EXPR` or `always EXPR`, got '{}'",
+                        i + 1,
+                        line
+                    ))));
+                }
+            };
+            assertions.push(Assertion::new(kind, expr)?)
+        }
+        Ok(assertions)
+    }
+
+    /// Get the kind of the assertion.
+    pub fn kind(&self) -> AssertionKind {
+        self.kind
+    }
+
+    /// Get the expression of the assertion.
+    pub fn expr(&self) -> &str {
+        &self.expr
+    }
+
+    /// Find the first `count` violations of the assertion. A violation still ongoing at the end of
+    /// the dump ends at the last timestamp.
+    fn violations(
+        &self,
+        signaldb: &SignalDB,
+        count: usize,
+    ) -> Result<Vec<AssertionViolation>, Box<dyn Error>> {
+        let ast = match self.kind {
+            AssertionKind::Never => self.ast.clone(),
+            AssertionKind::Always => ExprAst::Not(Box::new(self.ast.clone())),
+        };
+        let mut search = Search::from_ast(ast);
+        search.search_first(signaldb, count)?;
+        let mut periods = search.into_findings(signaldb, signaldb.get_time())?;
+        periods.truncate(count);
+
+        let mut violations = Vec::new();
+        for period in periods {
+            let (TimeDescr::Point(begin) | TimeDescr::Period(begin, _)) = period;
+            let mut values = Vec::new();
+            for id in self.ast.signal_ids() {
+                values.push((id.to_string(), signaldb.value_at(id, begin)?))
+            }
+            violations.push(AssertionViolation { period, values })
+        }
+        Ok(violations)
+    }
+}
+
+impl fmt::Display for Assertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            AssertionKind::Never => write!(f, "never {}", self.expr),
+            AssertionKind::Always => write!(f, "always {}", self.expr),
+        }
+    }
+}
+
+impl AssertionReport {
+    /// Check assertions on the whole dump, keeping the first `REPORTED_VIOLATIONS` violations of
+    /// each of them. The search of the violations stops as soon as they are found.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{Assertion, AssertionKind, AssertionReport};
+    /// use dwfv::signaldb::SignalDB;
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 valid $end
+    /// $var wire 4 1 data $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// bx 1
+    /// #10
+    /// 10
+    /// b1 1
+    /// #20
+    /// 00
+    /// #30
+    /// 10
+    /// bz 1
+    /// #40
+    /// 00
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let assertions = vec![
+    ///     Assertion::new(AssertionKind::Never, "$0 = 1 and $1 is unknown").unwrap(),
+    ///     Assertion::new(AssertionKind::Always, "$0 = 0 or $1 = 1").unwrap(),
+    ///     Assertion::new(AssertionKind::Never, "$1 = 2").unwrap(),
+    /// ];
+    /// let report = AssertionReport::check(&db, &assertions).unwrap();
+    /// assert_eq!(report.failures(), 2);
+    /// assert!(!report.is_success());
+    ///
+    /// let mut buf = Vec::new();
+    /// report.format(&db, &mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "FAIL never $0 = 1 and $1 is unknown\n  30ps-40ps\n    \
+    ///     0 (top.valid) = h1\n    1 (top.data[:4]) = hz\n\
+    ///     FAIL always $0 = 0 or $1 = 1\n  30ps-40ps\n    \
+    ///     0 (top.valid) = h1\n    1 (top.data[:4]) = hz\n\
+    ///     PASS never $1 = 2\n"
+    /// );
+    /// ```
+    pub fn check(
+        signaldb: &SignalDB,
+        assertions: &[Assertion],
+    ) -> Result<AssertionReport, Box<dyn Error>> {
+        let mut results = Vec::new();
+        for assertion in assertions {
+            // One more violation is looked for to know whether some of them are not reported.
+            let violations = assertion.violations(signaldb, REPORTED_VIOLATIONS + 1)?;
+            results.push((assertion.clone(), violations))
+        }
+        Ok(AssertionReport { results })
+    }
+
+    /// Get the assertions along with their violations.
+    pub fn results(&self) -> &[(Assertion, Vec<AssertionViolation>)] {
+        &self.results
+    }
+
+    /// Get the number of failed assertions.
+    pub fn failures(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, violations)| !violations.is_empty())
+            .count()
+    }
+
+    /// Check whether all the assertions hold.
+    pub fn is_success(&self) -> bool {
+        self.failures() == 0
+    }
+
+    /// Get the time periods of the violations.
+    pub fn findings(&self) -> Vec<TimeDescr> {
+        self.results
+            .iter()
+            .flat_map(|(_, violations)| violations.iter().map(|v| v.period))
+            .collect()
+    }
+
+    /// Format the result of each assertion, along with its first violations and the values of its
+    /// signals at the beginning of each of them.
+    pub fn format(&self, signaldb: &SignalDB, output: &mut dyn io::Write) {
+        for (assertion, violations) in &self.results {
+            if violations.is_empty() {
+                let _ = writeln!(output, "PASS {}", assertion);
+                continue;
+            }
+            let _ = writeln!(output, "FAIL {}", assertion);
+            for violation in violations.iter().take(REPORTED_VIOLATIONS) {
+                let _ = writeln!(output, "  {}", violation.period);
+                for (id, value) in &violation.values {
+                    let name = signaldb.get_signal_fullname(id).unwrap_or_default();
+                    let _ = writeln!(output, "    {} ({}) = {}", id, name, value);
+                }
+            }
+            if violations.len() > REPORTED_VIOLATIONS {
+                let _ = writeln!(output, "  ...");
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: MIT
+use super::{Radix, csv_field, json_string, select_signals};
+use crate::signaldb::{SignalDB, SignalValue, Timestamp};
+use std::io;
+
+/// Change of the value of a signal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueChange {
+    /// Time of the change
+    pub timestamp: Timestamp,
+    /// Identifier of the signal
+    pub signal_id: String,
+    /// Full name of the signal
+    pub fullname: String,
+    /// Value of the signal before the change
+    pub old: SignalValue,
+    /// Value of the signal after the change
+    pub new: SignalValue,
+}
+
+/// Chronological list of the value changes of several signals
+#[derive(Debug, Default)]
+pub struct ChangeLog {
+    changes: Vec<ValueChange>,
+}
+
+impl ChangeLog {
+    /// Collect the value changes of the signals matching a glob pattern (or of all the signals if
+    /// no pattern is given) between `begin` and `end`, both included. The changes happening at
+    /// the same time are sorted by signal identifier, and the value of a signal before its first
+    /// change is undefined (`u`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{ChangeLog, Radix};
+    /// use dwfv::signaldb::SignalDB;
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 valid $end
+    /// $var wire 8 1 data $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// b0 1
+    /// #10
+    /// 10
+    /// b101010 1
+    /// #20
+    /// 00
+    /// #30
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let log = ChangeLog::collect(&db, None, None, None);
+    /// assert_eq!(log.changes().len(), 5);
+    ///
+    /// let mut buf = Vec::new();
+    /// log.format_csv(Radix::Hex, &mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "time,signal,old,new\n\
+    ///     0ps,top.valid,u,0\n\
+    ///     0ps,top.data[:8],uu,00\n\
+    ///     10ps,top.valid,0,1\n\
+    ///     10ps,top.data[:8],00,2A\n\
+    ///     20ps,top.valid,1,0\n"
+    /// );
+    ///
+    /// let log = ChangeLog::collect(&db, Some("data"), None, None);
+    /// let mut buf = Vec::new();
+    /// log.format_jsonl(Radix::Dec, &mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "{\"time\": \"0ps\", \"signal\": \"top.data[:8]\", \"old\": \"u\", \"new\": \"0\"}\n\
+    ///     {\"time\": \"10ps\", \"signal\": \"top.data[:8]\", \"old\": \"0\", \"new\": \"42\"}\n"
+    /// );
+    /// ```
+    pub fn collect(
+        signaldb: &SignalDB,
+        pattern: Option<&str>,
+        begin: Option<Timestamp>,
+        end: Option<Timestamp>,
+    ) -> ChangeLog {
+        let ids = select_signals(signaldb, pattern);
+        let begin = begin.unwrap_or_else(Timestamp::origin);
+        let end = end.unwrap_or_else(|| signaldb.get_time());
+        let mut changes = signaldb.with_signals(|signals| {
+            let mut changes = Vec::new();
+            for id in &ids {
+                let signal = &signals[id];
+                let fullname = signal.get_fullname();
+                let mut old = signal.value_at(begin - begin.derive(1));
+                for (timestamp, value) in signal.events() {
+                    if timestamp < begin {
+                        continue;
+                    } else if timestamp > end {
+                        break;
+                    }
+                    changes.push(ValueChange {
+                        timestamp,
+                        signal_id: id.clone(),
+                        fullname: fullname.clone(),
+                        old,
+                        new: value.clone(),
+                    });
+                    old = value.clone()
+                }
+            }
+            changes
+        });
+        // The sort is stable, so the changes remain sorted by signal identifier.
+        changes.sort_by_key(|change| change.timestamp);
+        ChangeLog { changes }
+    }
+
+    /// Get the value changes, sorted by time.
+    pub fn changes(&self) -> &[ValueChange] {
+        &self.changes
+    }
+
+    /// Format the changes in CSV.
+    pub fn format_csv(&self, radix: Radix, output: &mut dyn io::Write) {
+        let _ = writeln!(output, "time,signal,old,new");
+        for change in &self.changes {
+            let _ = writeln!(
+                output,
+                "{},{},{},{}",
+                change.timestamp,
+                csv_field(&change.fullname),
+                csv_field(&radix.format(&change.old)),
+                csv_field(&radix.format(&change.new))
+            );
+        }
+    }
+
+    fn json_object(change: &ValueChange, radix: Radix) -> String {
+        format!(
+            "{{\"time\": {}, \"signal\": {}, \"old\": {}, \"new\": {}}}",
+            json_string(&change.timestamp.to_string()),
+            json_string(&change.fullname),
+            json_string(&radix.format(&change.old)),
+            json_string(&radix.format(&change.new))
+        )
+    }
+
+    /// Format the changes as a JSON array.
+    pub fn format_json(&self, radix: Radix, output: &mut dyn io::Write) {
+        let changes = self
+            .changes
+            .iter()
+            .map(|change| format!("  {}", ChangeLog::json_object(change, radix)))
+            .collect::<Vec<_>>();
+        if changes.is_empty() {
+            let _ = writeln!(output, "[]");
+        } else {
+            let _ = writeln!(output, "[\n{}\n]", changes.join(",\n"));
+        }
+    }
+
+    /// Format the changes in JSON Lines, with one object per line.
+    pub fn format_jsonl(&self, radix: Radix, output: &mut dyn io::Write) {
+        for change in &self.changes {
+            let _ = writeln!(output, "{}", ChangeLog::json_object(change, radix));
+        }
+    }
+}
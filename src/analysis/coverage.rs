@@ -0,0 +1,314 @@
+// SPDX-License-Identifier: MIT
+use super::{json_string, select_signals};
+use crate::signaldb::{SignalDB, Timestamp};
+use std::collections::BTreeMap;
+use std::io;
+
+/// Transitions of a single bit
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct BitToggles {
+    /// Number of `0 -> 1` transitions
+    pub rises: usize,
+    /// Number of `1 -> 0` transitions
+    pub falls: usize,
+}
+
+/// Toggle coverage of a signal
+#[derive(Debug, Clone)]
+pub struct SignalCoverage {
+    /// Identifier of the signal
+    pub id: String,
+    /// Full name of the signal
+    pub fullname: String,
+    /// Hierarchical name of the scope containing the signal
+    pub scope: String,
+    /// Transitions of each bit, indexed from the least significant bit
+    pub bits: Vec<BitToggles>,
+}
+
+/// Toggle coverage of a scope, including its sub-scopes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeCoverage {
+    /// Hierarchical name of the scope
+    pub name: String,
+    /// Number of bits in the scope
+    pub bits: usize,
+    /// Number of bits that rose and fell at least once
+    pub covered: usize,
+}
+
+/// Result of a toggle coverage analysis
+#[derive(Debug, Default)]
+pub struct CoverageReport {
+    signals: Vec<SignalCoverage>,
+}
+
+impl BitToggles {
+    /// Check if the bit rose and fell at least once.
+    pub fn is_covered(&self) -> bool {
+        self.rises > 0 && self.falls > 0
+    }
+}
+
+impl SignalCoverage {
+    /// Get the number of bits that rose and fell at least once.
+    pub fn covered(&self) -> usize {
+        self.bits.iter().filter(|b| b.is_covered()).count()
+    }
+
+    /// Get the total number of transitions of the bits.
+    pub fn toggles(&self) -> usize {
+        self.bits.iter().map(|b| b.rises + b.falls).sum()
+    }
+}
+
+fn percent(covered: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        covered as f64 * 100.0 / total as f64
+    }
+}
+
+impl CoverageReport {
+    /// Compute the toggle coverage of the signals matching a glob pattern (or of all the signals
+    /// if no pattern is given) between `begin` and `end` (both included). The whole dump is
+    /// considered if no time window is given.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::CoverageReport;
+    /// use dwfv::signaldb::SignalDB;
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 clk $end
+    /// $var wire 2 1 data $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// b00 1
+    /// #10
+    /// 10
+    /// b01 1
+    /// #20
+    /// 00
+    /// b10 1
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let report = CoverageReport::toggle(&db, None, None, None);
+    ///
+    /// let mut buf = Vec::new();
+    /// report.format(&mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "0 (top.clk): 1/1 bits toggled\n\
+    ///     1 (top.data[:2]): 1/2 bits toggled\n  \
+    ///     bit 1: rose 1 time, never fell\n\
+    ///     top: 2/3 bits toggled (66.7%)\n"
+    /// );
+    /// ```
+    pub fn toggle(
+        signaldb: &SignalDB,
+        pattern: Option<&str>,
+        begin: Option<Timestamp>,
+        end: Option<Timestamp>,
+    ) -> CoverageReport {
+        let ids = select_signals(signaldb, pattern);
+        let begin = begin.unwrap_or_else(Timestamp::origin);
+        let end = end.unwrap_or_else(|| signaldb.get_time());
+        let end = end + end.derive(1);
+
+        signaldb.with_signals(|signals| CoverageReport {
+            signals: ids
+                .iter()
+                .map(|id| {
+                    let signal = &signals[id];
+                    SignalCoverage {
+                        id: id.clone(),
+                        fullname: signal.get_fullname(),
+                        scope: signal
+                            .path
+                            .iter()
+                            .skip(1)
+                            .cloned()
+                            .collect::<Vec<_>>()
+                            .join("."),
+                        bits: signal
+                            .count_bit_toggles(begin, end)
+                            .into_iter()
+                            .map(|(rises, falls)| BitToggles { rises, falls })
+                            .collect(),
+                    }
+                })
+                .collect(),
+        })
+    }
+
+    /// Get the coverage of each signal.
+    pub fn signals(&self) -> &[SignalCoverage] {
+        &self.signals
+    }
+
+    /// Get the coverage aggregated per scope.
+    pub fn scopes(&self) -> Vec<ScopeCoverage> {
+        let mut scopes: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+        for signal in &self.signals {
+            let mut prefixes = signal
+                .scope
+                .match_indices('.')
+                .map(|(i, _)| &signal.scope[..i])
+                .collect::<Vec<_>>();
+            if !signal.scope.is_empty() {
+                prefixes.push(&signal.scope)
+            }
+            for prefix in prefixes {
+                let (bits, covered) = scopes.entry(prefix).or_default();
+                *bits += signal.bits.len();
+                *covered += signal.covered();
+            }
+        }
+        scopes
+            .into_iter()
+            .map(|(name, (bits, covered))| ScopeCoverage {
+                name: name.to_string(),
+                bits,
+                covered,
+            })
+            .collect()
+    }
+
+    /// Format the coverage as text, detailing the bits that did not toggle.
+    pub fn format(&self, output: &mut dyn io::Write) {
+        for signal in &self.signals {
+            let _ = writeln!(
+                output,
+                "{} ({}): {}/{} bits toggled",
+                signal.id,
+                signal.fullname,
+                signal.covered(),
+                signal.bits.len()
+            );
+            for (i, bit) in signal.bits.iter().enumerate() {
+                if bit.is_covered() {
+                    continue;
+                }
+                let describe = |n, what| match n {
+                    0 => format!("never {}", what),
+                    1 => format!("{} 1 time", what),
+                    n => format!("{} {} times", what, n),
+                };
+                let _ = writeln!(
+                    output,
+                    "  bit {}: {}, {}",
+                    i,
+                    describe(bit.rises, "rose"),
+                    describe(bit.falls, "fell")
+                );
+            }
+        }
+        for scope in self.scopes() {
+            let _ = writeln!(
+                output,
+                "{}: {}/{} bits toggled ({:.1}%)",
+                scope.name,
+                scope.covered,
+                scope.bits,
+                percent(scope.covered, scope.bits)
+            );
+        }
+    }
+
+    /// Format the coverage as JSON.
+    pub fn format_json(&self, output: &mut dyn io::Write) {
+        let signals = self
+            .signals
+            .iter()
+            .map(|signal| {
+                let bits = signal
+                    .bits
+                    .iter()
+                    .map(|bit| format!("{{\"rises\": {}, \"falls\": {}}}", bit.rises, bit.falls))
+                    .collect::<Vec<_>>();
+                format!(
+                    "    {{\"id\": {}, \"name\": {}, \"scope\": {}, \"covered\": {}, \
+                    \"bits\": [{}]}}",
+                    json_string(&signal.id),
+                    json_string(&signal.fullname),
+                    json_string(&signal.scope),
+                    signal.covered(),
+                    bits.join(", ")
+                )
+            })
+            .collect::<Vec<_>>();
+        let scopes = self
+            .scopes()
+            .iter()
+            .map(|scope| {
+                format!(
+                    "    {{\"name\": {}, \"bits\": {}, \"covered\": {}}}",
+                    json_string(&scope.name),
+                    scope.bits,
+                    scope.covered
+                )
+            })
+            .collect::<Vec<_>>();
+        let _ = writeln!(
+            output,
+            "{{\n  \"signals\": [\n{}\n  ],\n  \"scopes\": [\n{}\n  ]\n}}",
+            signals.join(",\n"),
+            scopes.join(",\n")
+        );
+    }
+
+    /// Format the coverage as an LCOV tracefile, with one record per scope. Each signal is
+    /// reported as a function and each bit as a pair of branches (rise and fall).
+    pub fn format_lcov(&self, output: &mut dyn io::Write) {
+        let mut scopes: BTreeMap<&str, Vec<&SignalCoverage>> = BTreeMap::new();
+        for signal in &self.signals {
+            scopes.entry(&signal.scope).or_default().push(signal)
+        }
+
+        for (scope, signals) in scopes {
+            let _ = writeln!(output, "TN:toggle\nSF:{}", scope);
+            for (line, signal) in signals.iter().enumerate() {
+                let _ = writeln!(output, "FN:{},{}", line + 1, signal.fullname);
+            }
+            for signal in &signals {
+                let _ = writeln!(output, "FNDA:{},{}", signal.toggles(), signal.fullname);
+            }
+            let _ = writeln!(
+                output,
+                "FNF:{}\nFNH:{}",
+                signals.len(),
+                signals
+                    .iter()
+                    .filter(|s| s.covered() == s.bits.len())
+                    .count()
+            );
+
+            let (mut found, mut hit) = (0, 0);
+            for (line, signal) in signals.iter().enumerate() {
+                for (i, bit) in signal.bits.iter().enumerate() {
+                    for (branch, count) in [bit.rises, bit.falls].iter().enumerate() {
+                        let _ = writeln!(output, "BRDA:{},{},{},{}", line + 1, i, branch, count);
+                        found += 1;
+                        hit += (*count > 0) as usize;
+                    }
+                }
+            }
+            let _ = writeln!(output, "BRF:{}\nBRH:{}", found, hit);
+
+            for (line, signal) in signals.iter().enumerate() {
+                let _ = writeln!(output, "DA:{},{}", line + 1, signal.toggles());
+            }
+            let _ = writeln!(
+                output,
+                "LF:{}\nLH:{}\nend_of_record",
+                signals.len(),
+                signals.iter().filter(|s| s.toggles() > 0).count()
+            );
+        }
+    }
+}
@@ -0,0 +1,369 @@
+// SPDX-License-Identifier: MIT
+use super::{AnalysisError, check_window, parse_value, resolve_signal};
+use crate::signaldb::{SignalDB, SignalValue, Timestamp};
+use std::error::Error;
+use std::io;
+
+/// Translation of the values of a state register into state names
+#[derive(Debug, Clone, Default)]
+pub struct StateNames {
+    names: Vec<(SignalValue, String)>,
+}
+
+/// State observed in a state register
+#[derive(Debug, Clone)]
+pub struct State {
+    /// Value of the state register
+    pub value: SignalValue,
+    /// Name of the state, if known
+    pub name: Option<String>,
+    /// Number of times the state was entered
+    pub visits: usize,
+    /// Time spent in the state
+    pub time: Timestamp,
+}
+
+/// Transition observed between two states
+#[derive(Debug, Clone)]
+pub struct Transition {
+    /// Value of the state register before the transition
+    pub from: SignalValue,
+    /// Value of the state register after the transition
+    pub to: SignalValue,
+    /// Number of occurrences of the transition
+    pub count: usize,
+    /// Timestamp of the first occurrence
+    pub first: Timestamp,
+    /// Timestamp of the last occurrence
+    pub last: Timestamp,
+}
+
+/// States and transitions of a state machine extracted from its state register
+#[derive(Debug)]
+pub struct FsmReport {
+    signal_id: String,
+    names: StateNames,
+    states: Vec<State>,
+    transitions: Vec<Transition>,
+}
+
+impl StateNames {
+    /// Parse state names with the format `VALUE=NAME`, separated by commas or new lines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::StateNames;
+    /// use dwfv::signaldb::SignalValue;
+    /// let names = StateNames::parse("0=IDLE, h1=BUSY\n2=DONE").unwrap();
+    /// assert_eq!(names.get(&SignalValue::new(1)), Some("BUSY"));
+    /// assert_eq!(names.get(&SignalValue::new(3)), None);
+    /// assert!(StateNames::parse("IDLE").is_err());
+    /// ```
+    pub fn parse(s: &str) -> Result<StateNames, AnalysisError> {
+        let mut names = Vec::new();
+        for entry in s
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+        {
+            let (value, name) = entry.split_once('=').ok_or_else(|| {
+                AnalysisError::new(&format!("expected VALUE=NAME, got '{}'", entry))
+            })?;
+            names.push((parse_value(value.trim())?, name.trim().to_string()))
+        }
+        Ok(StateNames { names })
+    }
+
+    /// Get the name associated to a value.
+    pub fn get(&self, value: &SignalValue) -> Option<&str> {
+        self.names
+            .iter()
+            .find(|(v, _)| v == value)
+            .map(|(_, name)| name.as_str())
+    }
+
+    fn label(&self, value: &SignalValue) -> String {
+        match self.get(value) {
+            Some(name) => name.to_string(),
+            None => value.to_string(),
+        }
+    }
+}
+
+/// Escape a string to be included in a quoted identifier of the DOT language.
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl FsmReport {
+    /// Extract the states and the transitions of a state register from `begin` included to `end`
+    /// excluded. The whole dump is considered if no time window is given, and the window is cut
+    /// at the end of the dump.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{FsmReport, StateNames};
+    /// use dwfv::signaldb::{Scale, SignalDB, Timestamp};
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 2 0 state $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// b00 0
+    /// #10
+    /// b01 0
+    /// #15
+    /// b00 0
+    /// #20
+    /// b01 0
+    /// #30
+    /// b10 0
+    /// #40
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let names = StateNames::parse("0=IDLE,1=BUSY,2=DONE,3=ERROR").unwrap();
+    /// let report = FsmReport::extract(&db, "state", names, None, None).unwrap();
+    ///
+    /// let mut buf = Vec::new();
+    /// report.format(&db, &mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "0 (top.state[:2])\n\
+    ///     States:\n  \
+    ///     IDLE (h0): 2 visits, 15ps\n  \
+    ///     BUSY (h1): 2 visits, 15ps\n  \
+    ///     DONE (h2): 1 visit, 10ps\n  \
+    ///     ERROR (h3): never visited\n\
+    ///     Transitions:\n  \
+    ///     IDLE -> BUSY: 2 times, first at 10ps, last at 20ps\n  \
+    ///     BUSY -> IDLE: 1 time, first at 15ps, last at 15ps\n  \
+    ///     BUSY -> DONE: 1 time, first at 30ps, last at 30ps\n"
+    /// );
+    ///
+    /// // The transition at the end of the window is not part of it.
+    /// let end = Some(Timestamp::new(30, Scale::Picosecond));
+    /// let report = FsmReport::extract(&db, "state", StateNames::default(), None, end).unwrap();
+    /// assert_eq!(report.transitions().len(), 2);
+    ///
+    /// let (begin, end) = (Some(Timestamp::new(20, Scale::Picosecond)), Some(Timestamp::origin()));
+    /// assert!(FsmReport::extract(&db, "state", StateNames::default(), begin, end).is_err());
+    /// ```
+    pub fn extract(
+        signaldb: &SignalDB,
+        signal: &str,
+        names: StateNames,
+        begin: Option<Timestamp>,
+        end: Option<Timestamp>,
+    ) -> Result<FsmReport, Box<dyn Error>> {
+        let last = signaldb.get_last_timestamp();
+        let begin = begin.unwrap_or_else(Timestamp::origin);
+        let end = end.map_or(last, |end| end.min(last));
+        check_window(begin, end)?;
+
+        signaldb.with_signals(|signals| {
+            let signal_id = resolve_signal(signals, signal)?;
+            let signal = &signals[&signal_id];
+            let mut report = FsmReport {
+                signal_id,
+                names,
+                states: Vec::new(),
+                transitions: Vec::new(),
+            };
+
+            let mut current = signal.value_at(begin);
+            let mut since = begin;
+            report.enter(&current);
+            for (timestamp, value) in signal.events() {
+                if timestamp <= begin {
+                    continue;
+                } else if timestamp >= end {
+                    break;
+                }
+                report.leave(&current, timestamp - since);
+                report.transition(&current, value, timestamp);
+                report.enter(value);
+                current = value.clone();
+                since = timestamp
+            }
+            report.leave(&current, end - since);
+            Ok(report)
+        })
+    }
+
+    fn state(&mut self, value: &SignalValue) -> &mut State {
+        match self.states.iter().position(|s| s.value == *value) {
+            Some(index) => &mut self.states[index],
+            None => {
+                self.states.push(State {
+                    value: value.clone(),
+                    name: self.names.get(value).map(String::from),
+                    visits: 0,
+                    time: Timestamp::origin(),
+                });
+                self.states.last_mut().unwrap()
+            }
+        }
+    }
+
+    fn enter(&mut self, value: &SignalValue) {
+        self.state(value).visits += 1
+    }
+
+    fn leave(&mut self, value: &SignalValue, time: Timestamp) {
+        self.state(value).time += time
+    }
+
+    fn transition(&mut self, from: &SignalValue, to: &SignalValue, timestamp: Timestamp) {
+        match self
+            .transitions
+            .iter_mut()
+            .find(|t| t.from == *from && t.to == *to)
+        {
+            Some(transition) => {
+                transition.count += 1;
+                transition.last = timestamp
+            }
+            None => self.transitions.push(Transition {
+                from: from.clone(),
+                to: to.clone(),
+                count: 1,
+                first: timestamp,
+                last: timestamp,
+            }),
+        }
+    }
+
+    /// Get the observed states, in order of first appearance.
+    pub fn states(&self) -> &[State] {
+        &self.states
+    }
+
+    /// Get the named states that were never visited.
+    pub fn unvisited(&self) -> Vec<(&SignalValue, &str)> {
+        self.names
+            .names
+            .iter()
+            .filter(|(value, _)| self.states.iter().all(|s| s.value != *value))
+            .map(|(value, name)| (value, name.as_str()))
+            .collect()
+    }
+
+    /// Get the observed transitions, in order of first occurrence.
+    pub fn transitions(&self) -> &[Transition] {
+        &self.transitions
+    }
+
+    /// Format the states and the transitions.
+    pub fn format(&self, signaldb: &SignalDB, output: &mut dyn io::Write) {
+        let fullname = signaldb
+            .get_signal_fullname(&self.signal_id)
+            .unwrap_or_default();
+        let _ = writeln!(output, "{} ({})\nStates:", self.signal_id, fullname);
+
+        let mut states = self.states.iter().collect::<Vec<_>>();
+        states.sort_by_key(|s| {
+            self.names
+                .names
+                .iter()
+                .position(|(v, _)| *v == s.value)
+                .unwrap_or(usize::MAX)
+        });
+        for state in states {
+            let label = match &state.name {
+                Some(name) => format!("{} ({})", name, state.value),
+                None => state.value.to_string(),
+            };
+            let _ = writeln!(
+                output,
+                "  {}: {} visit{}, {}",
+                label,
+                state.visits,
+                if state.visits == 1 { "" } else { "s" },
+                state.time
+            );
+        }
+        for (value, name) in self.unvisited() {
+            let _ = writeln!(output, "  {} ({}): never visited", name, value);
+        }
+
+        let _ = writeln!(output, "Transitions:");
+        for t in &self.transitions {
+            let _ = writeln!(
+                output,
+                "  {} -> {}: {} time{}, first at {}, last at {}",
+                self.names.label(&t.from),
+                self.names.label(&t.to),
+                t.count,
+                if t.count == 1 { "" } else { "s" },
+                t.first,
+                t.last
+            );
+        }
+    }
+
+    /// Format the transition graph in the Graphviz DOT language. Named states that were never
+    /// visited are drawn with a dashed outline.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{FsmReport, StateNames};
+    /// use dwfv::signaldb::SignalDB;
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 state $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// #10
+    /// 10
+    /// #20
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let names = StateNames::parse(r#"0="IDLE",1=A\B,2=DONE"#).unwrap();
+    /// let report = FsmReport::extract(&db, "state", names, None, None).unwrap();
+    ///
+    /// let mut buf = Vec::new();
+    /// report.format_dot(&mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     r#"digraph fsm {
+    ///   "\"IDLE\"" [label="\"IDLE\"\n10ps"];
+    ///   "A\\B" [label="A\\B\n10ps"];
+    ///   "DONE" [style=dashed];
+    ///   "\"IDLE\"" -> "A\\B" [label="1"];
+    /// }
+    /// "#
+    /// );
+    /// ```
+    pub fn format_dot(&self, output: &mut dyn io::Write) {
+        let _ = writeln!(output, "digraph fsm {{");
+        for state in &self.states {
+            let label = self.names.label(&state.value);
+            let _ = writeln!(
+                output,
+                "  \"{}\" [label=\"{}\\n{}\"];",
+                dot_escape(&label),
+                dot_escape(&label),
+                state.time
+            );
+        }
+        for (_, name) in self.unvisited() {
+            let _ = writeln!(output, "  \"{}\" [style=dashed];", dot_escape(name));
+        }
+        for t in &self.transitions {
+            let _ = writeln!(
+                output,
+                "  \"{}\" -> \"{}\" [label=\"{}\"];",
+                dot_escape(&self.names.label(&t.from)),
+                dot_escape(&self.names.label(&t.to)),
+                t.count
+            );
+        }
+        let _ = writeln!(output, "}}");
+    }
+}
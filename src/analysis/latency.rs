@@ -0,0 +1,321 @@
+// SPDX-License-Identifier: MIT
+use super::{AnalysisError, resolve_signal};
+use crate::signaldb::{SignalDB, SignalValue, TimeDescr, Timestamp};
+use std::error::Error;
+use std::io;
+
+const HISTOGRAM_BINS: usize = 10;
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// Strategy used to pair the start findings with the end findings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pairing {
+    /// Each end is paired with the oldest pending start
+    Fifo,
+    /// Each end is paired with the oldest pending start having the same identifier, the
+    /// identifiers being the values of two signals (sampled at the start and at the end)
+    Id(String, String),
+}
+
+/// Latencies between the findings of two search expressions
+#[derive(Debug)]
+pub struct LatencyReport {
+    pairs: Vec<(Timestamp, Timestamp)>,
+    unmatched_starts: usize,
+    unmatched_ends: usize,
+}
+
+impl Pairing {
+    /// Parse an ID pairing with the format `SIGNAL` (same identifier signal for the starts and
+    /// the ends) or `START_SIGNAL,END_SIGNAL`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::Pairing;
+    /// assert_eq!(Pairing::parse_id("id"), Pairing::Id("id".to_string(), "id".to_string()));
+    /// assert_eq!(
+    ///     Pairing::parse_id("req_id,ack_id"),
+    ///     Pairing::Id("req_id".to_string(), "ack_id".to_string())
+    /// );
+    /// ```
+    pub fn parse_id(spec: &str) -> Pairing {
+        match spec.split_once(',') {
+            Some((start, end)) => Pairing::Id(start.trim().to_string(), end.trim().to_string()),
+            None => Pairing::Id(spec.trim().to_string(), spec.trim().to_string()),
+        }
+    }
+}
+
+/// Split a latency specification with the format `START_EXPR => END_EXPR`.
+///
+/// # Example
+///
+/// ```
+/// use dwfv::analysis::parse_latency;
+/// assert_eq!(
+///     parse_latency("$req <- 1 => $ack <- 1").unwrap(),
+///     ("$req <- 1", "$ack <- 1")
+/// );
+/// assert!(parse_latency("$req <- 1").is_err());
+/// ```
+pub fn parse_latency(spec: &str) -> Result<(&str, &str), AnalysisError> {
+    spec.split_once("=>")
+        .map(|(start, end)| (start.trim(), end.trim()))
+        .ok_or_else(|| AnalysisError::new(&format!("expected START => END, got '{}'", spec)))
+}
+
+fn all_findings(signaldb: &SignalDB, expr: &str) -> Result<Vec<Timestamp>, Box<dyn Error>> {
+    signaldb.search(expr)?;
+    let mut findings = Vec::new();
+    let mut next = signaldb.get_first_finding(expr)?;
+    while let Some(timestamp) = next {
+        findings.push(timestamp);
+        next = signaldb.get_next_finding(expr, timestamp)?;
+    }
+    Ok(findings)
+}
+
+impl LatencyReport {
+    /// Pair each finding of the `start` expression with a following finding of the `end`
+    /// expression, and measure the time between them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{LatencyReport, Pairing};
+    /// use dwfv::signaldb::{Scale, SignalDB, Timestamp};
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 req $end
+    /// $var wire 1 1 ack $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// 01
+    /// #10
+    /// 10
+    /// #11
+    /// 00
+    /// #12
+    /// 10
+    /// #13
+    /// 00
+    /// #20
+    /// 11
+    /// #21
+    /// 01
+    /// #40
+    /// 11
+    /// #41
+    /// 01
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let report = LatencyReport::measure(&db, "$0 <- 1", "$1 <- 1", &Pairing::Fifo).unwrap();
+    /// assert_eq!(report.len(), 2);
+    /// assert_eq!(report.min().unwrap(), Timestamp::new(10, Scale::Picosecond));
+    /// assert_eq!(report.max().unwrap(), Timestamp::new(28, Scale::Picosecond));
+    /// assert_eq!(report.mean().unwrap(), Timestamp::new(19, Scale::Picosecond));
+    /// assert_eq!(report.percentile(50.0).unwrap(), Timestamp::new(10, Scale::Picosecond));
+    /// ```
+    pub fn measure(
+        signaldb: &SignalDB,
+        start: &str,
+        end: &str,
+        pairing: &Pairing,
+    ) -> Result<LatencyReport, Box<dyn Error>> {
+        let starts = all_findings(signaldb, start)?;
+        let ends = all_findings(signaldb, end)?;
+
+        let (start_ids, end_ids) = match pairing {
+            Pairing::Fifo => (Vec::new(), Vec::new()),
+            Pairing::Id(start_id, end_id) => signaldb.with_signals(|signals| {
+                let start_id = &signals[&resolve_signal(signals, start_id)?];
+                let end_id = &signals[&resolve_signal(signals, end_id)?];
+                Ok::<_, Box<dyn Error>>((
+                    starts.iter().map(|t| start_id.value_at(*t)).collect(),
+                    ends.iter()
+                        .map(|t| end_id.value_at(*t))
+                        .collect::<Vec<SignalValue>>(),
+                ))
+            })?,
+        };
+
+        let mut pending: Vec<usize> = Vec::new();
+        let mut pairs = Vec::new();
+        let mut unmatched_ends = 0;
+        let mut next_start = 0;
+        for (e, end) in ends.iter().enumerate() {
+            // An end can only be paired with a start occurring strictly before it.
+            while next_start < starts.len() && starts[next_start] < *end {
+                pending.push(next_start);
+                next_start += 1
+            }
+            let matching = match pairing {
+                Pairing::Fifo => (!pending.is_empty()).then_some(0),
+                Pairing::Id(_, _) => pending.iter().position(|s| start_ids[*s] == end_ids[e]),
+            };
+            match matching {
+                Some(index) => pairs.push((starts[pending.remove(index)], *end)),
+                None => unmatched_ends += 1,
+            }
+        }
+
+        Ok(LatencyReport {
+            pairs,
+            unmatched_starts: pending.len() + starts.len() - next_start,
+            unmatched_ends,
+        })
+    }
+
+    /// Get the paired start and end timestamps.
+    pub fn pairs(&self) -> &[(Timestamp, Timestamp)] {
+        &self.pairs
+    }
+
+    /// Get the number of pairs.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Check if no pair was found.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    fn sorted_latencies(&self) -> Vec<Timestamp> {
+        let mut latencies = self
+            .pairs
+            .iter()
+            .map(|(start, end)| *end - *start)
+            .collect::<Vec<_>>();
+        latencies.sort();
+        latencies
+    }
+
+    /// Get the minimum latency.
+    pub fn min(&self) -> Option<Timestamp> {
+        self.sorted_latencies().first().copied()
+    }
+
+    /// Get the maximum latency.
+    pub fn max(&self) -> Option<Timestamp> {
+        self.sorted_latencies().last().copied()
+    }
+
+    /// Get the mean latency, rounded down to the time scale of the dump.
+    pub fn mean(&self) -> Option<Timestamp> {
+        let latencies = self.sorted_latencies();
+        let sum = latencies.iter().copied().reduce(|a, b| a + b)?;
+        Some(sum / latencies.len() as i64)
+    }
+
+    /// Get a percentile of the latencies (nearest-rank method).
+    pub fn percentile(&self, p: f64) -> Option<Timestamp> {
+        let latencies = self.sorted_latencies();
+        let rank = (p / 100.0 * latencies.len() as f64).ceil() as usize;
+        latencies
+            .get(rank.clamp(1, latencies.len().max(1)) - 1)
+            .copied()
+    }
+
+    /// Get the time periods between the paired findings.
+    pub fn findings(&self) -> Vec<TimeDescr> {
+        self.pairs
+            .iter()
+            .map(|(start, end)| TimeDescr::Period(*start, *end))
+            .collect()
+    }
+
+    /// Format the statistics and the histogram of the latencies.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{LatencyReport, Pairing};
+    /// use dwfv::signaldb::SignalDB;
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 req $end
+    /// $var wire 1 1 ack $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// 01
+    /// #10
+    /// 10
+    /// #13
+    /// 11
+    /// #20
+    /// 00
+    /// 01
+    /// #30
+    /// 10
+    /// #37
+    /// 11
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let report = LatencyReport::measure(&db, "$0 <- 1", "$1 <- 1", &Pairing::Fifo).unwrap();
+    ///
+    /// let mut buf = Vec::new();
+    /// report.format(&mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "pairs: 2, unmatched starts: 0, unmatched ends: 0\n\
+    ///     min: 3ps, max: 7ps, mean: 5ps\n\
+    ///     p50: 3ps, p90: 7ps, p99: 7ps\n    \
+    ///     >= 3ps | ######################################## 1\n    \
+    ///     >= 4ps |  0\n    \
+    ///     >= 5ps |  0\n    \
+    ///     >= 6ps |  0\n    \
+    ///     >= 7ps | ######################################## 1\n"
+    /// );
+    /// ```
+    pub fn format(&self, output: &mut dyn io::Write) {
+        let _ = writeln!(
+            output,
+            "pairs: {}, unmatched starts: {}, unmatched ends: {}",
+            self.pairs.len(),
+            self.unmatched_starts,
+            self.unmatched_ends
+        );
+        let (Some(min), Some(max), Some(mean)) = (self.min(), self.max(), self.mean()) else {
+            return;
+        };
+        let _ = writeln!(output, "min: {}, max: {}, mean: {}", min, max, mean);
+        let _ = writeln!(
+            output,
+            "p50: {}, p90: {}, p99: {}",
+            self.percentile(50.0).unwrap(),
+            self.percentile(90.0).unwrap(),
+            self.percentile(99.0).unwrap()
+        );
+
+        // Each bin spans a whole number of ticks, with no more bins than distinct latencies.
+        let span = max - min;
+        let bins = if span.value == 0 {
+            1
+        } else {
+            HISTOGRAM_BINS.min(span.value as usize + 1)
+        };
+        let width = span.derive((span.value as usize).div_ceil(bins).max(1) as i64);
+        let mut histogram = vec![0; bins];
+        for latency in self.sorted_latencies() {
+            histogram[((latency - min) / width).min(bins - 1)] += 1
+        }
+
+        let highest = *histogram.iter().max().unwrap();
+        for (i, count) in histogram.iter().enumerate() {
+            let from = min + width * i as i64;
+            let bar = "#".repeat((count * HISTOGRAM_WIDTH).div_ceil(highest));
+            let label = if bins == 1 {
+                from.to_string()
+            } else {
+                format!(">= {}", from)
+            };
+            let _ = writeln!(output, "{:>10} | {} {}", label, bar, count);
+        }
+    }
+}
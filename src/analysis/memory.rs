@@ -0,0 +1,272 @@
+// SPDX-License-Identifier: MIT
+use super::{AnalysisError, parse_value, resolve_signal};
+use crate::signaldb::{SignalDB, SignalValue, Timestamp};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io;
+
+/// Number of words displayed on each line of a hex dump
+pub const WORDS_PER_LINE: u64 = 8;
+
+/// Signals of the write port of a memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WritePort {
+    /// Clock of the port, sampled on its rising edges
+    pub clock: String,
+    /// Write enable, active high
+    pub write_enable: String,
+    /// Address bus
+    pub address: String,
+    /// Data bus
+    pub data: String,
+}
+
+/// Write performed on a memory
+#[derive(Debug, Clone)]
+pub struct MemoryWrite {
+    /// Timestamp of the clock edge
+    pub timestamp: Timestamp,
+    /// Written address
+    pub address: u64,
+    /// Written data
+    pub data: SignalValue,
+}
+
+/// Content of a memory, reconstructed from the activity of its write port
+#[derive(Debug)]
+pub struct Memory {
+    width: usize,
+    writes: Vec<MemoryWrite>,
+}
+
+impl WritePort {
+    /// Parse a write port with the format `CLK,WE,ADDR,DATA`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::WritePort;
+    /// let port = WritePort::parse("clk,we,addr,wdata").unwrap();
+    /// assert_eq!(port.data, "wdata");
+    /// assert!(WritePort::parse("clk,we,addr").is_err());
+    /// ```
+    pub fn parse(spec: &str) -> Result<WritePort, AnalysisError> {
+        match spec.split(',').map(str::trim).collect::<Vec<_>>()[..] {
+            [clock, write_enable, address, data] => Ok(WritePort {
+                clock: clock.to_string(),
+                write_enable: write_enable.to_string(),
+                address: address.to_string(),
+                data: data.to_string(),
+            }),
+            _ => Err(AnalysisError::new(&format!(
+                "expected CLK,WE,ADDR,DATA, got '{}'",
+                spec
+            ))),
+        }
+    }
+}
+
+/// Parse an address, written in decimal or in hexadecimal (e.g. `42`, `h2a` or `0x2a`).
+///
+/// # Example
+///
+/// ```
+/// use dwfv::analysis::parse_address;
+/// assert_eq!(parse_address("42").unwrap(), 42);
+/// assert_eq!(parse_address("h2a").unwrap(), 42);
+/// assert_eq!(parse_address("0x2a").unwrap(), 42);
+/// assert!(parse_address("foo").is_err());
+/// ```
+pub fn parse_address(s: &str) -> Result<u64, AnalysisError> {
+    let s = s.trim();
+    let value = match s.strip_prefix("0x") {
+        Some(hex) => parse_value(&format!("h{}", hex)),
+        None => parse_value(s),
+    };
+    value
+        .ok()
+        .and_then(|v| v.to_u64())
+        .ok_or_else(|| AnalysisError::new(&format!("invalid address '{}'", s)))
+}
+
+impl Memory {
+    /// Reconstruct the writes performed on a memory. The write enable, the address and the data
+    /// are sampled just before each rising edge of the clock.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{Memory, WritePort};
+    /// use dwfv::signaldb::{Scale, SignalDB, SignalValue, Timestamp};
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 clk $end
+    /// $var wire 1 1 we $end
+    /// $var wire 4 2 addr $end
+    /// $var wire 8 3 data $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// 11
+    /// b0010 2
+    /// b00010001 3
+    /// #10
+    /// 10
+    /// b0011 2
+    /// b00100010 3
+    /// #15
+    /// 00
+    /// #20
+    /// 10
+    /// 01
+    /// #25
+    /// 00
+    /// #30
+    /// 10
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let port = WritePort::parse("clk,we,addr,data").unwrap();
+    /// let memory = Memory::reconstruct(&db, &port).unwrap();
+    /// let t = |v| Timestamp::new(v, Scale::Picosecond);
+    ///
+    /// assert_eq!(memory.value_at(2, t(15)), Some(SignalValue::new(0x11)));
+    /// assert_eq!(memory.value_at(3, t(15)), None);
+    /// assert_eq!(memory.value_at(3, t(20)), Some(SignalValue::new(0x22)));
+    /// assert_eq!(memory.last_write(3, t(30)).unwrap().timestamp, t(20));
+    ///
+    /// let mut buf = Vec::new();
+    /// memory.format_dump(t(30), &mut buf);
+    /// assert_eq!(String::from_utf8(buf).unwrap(), "00000000: -- -- 11 22 -- -- -- --\n");
+    /// ```
+    pub fn reconstruct(signaldb: &SignalDB, port: &WritePort) -> Result<Memory, Box<dyn Error>> {
+        signaldb.with_signals(|signals| {
+            let clock = &signals[&resolve_signal(signals, &port.clock)?];
+            let write_enable = &signals[&resolve_signal(signals, &port.write_enable)?];
+            let address = &signals[&resolve_signal(signals, &port.address)?];
+            let data = &signals[&resolve_signal(signals, &port.data)?];
+
+            let mut writes = Vec::new();
+            let mut edge = clock
+                .get_first_event()
+                .and_then(|t| clock.get_next_rising_edge(t - t.derive(1)));
+            while let Some(timestamp) = edge {
+                let sample = timestamp - timestamp.derive(1);
+                if write_enable.value_at(sample).to_u64() == Some(1)
+                    && let Some(addr) = address.value_at(sample).to_u64()
+                {
+                    writes.push(MemoryWrite {
+                        timestamp,
+                        address: addr,
+                        data: data.value_at(sample),
+                    })
+                }
+                edge = clock.get_next_rising_edge(timestamp)
+            }
+
+            Ok(Memory {
+                width: data.width,
+                writes,
+            })
+        })
+    }
+
+    /// Get the width of the words of the memory.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get all the writes performed on the memory.
+    pub fn writes(&self) -> &[MemoryWrite] {
+        &self.writes
+    }
+
+    /// Get the last write performed on an address at a given time.
+    pub fn last_write(&self, address: u64, timestamp: Timestamp) -> Option<&MemoryWrite> {
+        self.writes
+            .iter()
+            .rev()
+            .find(|w| w.address == address && w.timestamp <= timestamp)
+    }
+
+    /// Get the content of an address at a given time.
+    pub fn value_at(&self, address: u64, timestamp: Timestamp) -> Option<SignalValue> {
+        self.last_write(address, timestamp).map(|w| w.data.clone())
+    }
+
+    /// Get the content of all the written addresses at a given time.
+    pub fn contents_at(&self, timestamp: Timestamp) -> BTreeMap<u64, SignalValue> {
+        let mut contents = BTreeMap::new();
+        for write in self.writes.iter().take_while(|w| w.timestamp <= timestamp) {
+            contents.insert(write.address, write.data.clone());
+        }
+        contents
+    }
+
+    /// Format a word of the memory as a fixed-width hexadecimal number.
+    pub fn format_word(&self, word: Option<&SignalValue>) -> String {
+        let digits = self.width.div_ceil(4).max(1);
+        match word {
+            Some(value) => match value.to_u64() {
+                Some(v) => format!("{:0digits$x}", v, digits = digits),
+                None => "x".repeat(digits),
+            },
+            None => "-".repeat(digits),
+        }
+    }
+
+    /// Get the lines of the hex dump of the memory at a given time. Only the lines containing
+    /// written addresses are returned, each line starting with its first address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{Memory, WritePort};
+    /// use dwfv::signaldb::{Scale, SignalDB, Timestamp};
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 clk $end
+    /// $var wire 1 1 we $end
+    /// $var wire 64 2 addr $end
+    /// $var wire 8 3 data $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// 11
+    /// b1111111111111111111111111111111111111111111111111111111111111111 2
+    /// b00010001 3
+    /// #10
+    /// 10
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let port = WritePort::parse("clk,we,addr,data").unwrap();
+    /// let memory = Memory::reconstruct(&db, &port).unwrap();
+    /// assert_eq!(
+    ///     memory.dump(Timestamp::new(10, Scale::Picosecond)),
+    ///     vec![(0xffff_ffff_ffff_fff8, "-- -- -- -- -- -- -- 11".to_string())]
+    /// );
+    /// ```
+    pub fn dump(&self, timestamp: Timestamp) -> Vec<(u64, String)> {
+        let contents = self.contents_at(timestamp);
+        let mut lines: Vec<(u64, String)> = Vec::new();
+        for address in contents.keys() {
+            let base = address - address % WORDS_PER_LINE;
+            if lines.last().is_some_and(|(b, _)| *b == base) {
+                continue;
+            }
+            let words = (base..=base + (WORDS_PER_LINE - 1))
+                .map(|a| self.format_word(contents.get(&a)))
+                .collect::<Vec<_>>();
+            lines.push((base, words.join(" ")))
+        }
+        lines
+    }
+
+    /// Format the hex dump of the memory at a given time.
+    pub fn format_dump(&self, timestamp: Timestamp, output: &mut dyn io::Write) {
+        for (base, words) in self.dump(timestamp) {
+            let _ = writeln!(output, "{:08x}: {}", base, words);
+        }
+    }
+}
@@ -0,0 +1,331 @@
+// SPDX-License-Identifier: MIT
+use super::{AnalysisError, Duration, parse_value, resolve_signal, select_signals};
+use crate::signaldb::{Signal, SignalDB, SignalValue, TimeDescr, Timestamp};
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Constraint on the width of the pulses of a signal
+#[derive(Debug, Clone)]
+pub struct PulseCheck {
+    signal: String,
+    value: Option<SignalValue>,
+    min: Option<Duration>,
+    max: Option<Duration>,
+}
+
+/// Kind of violation reported by a `PulseCheck`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViolationKind {
+    /// The pulse is shorter than the minimum width
+    TooShort(Duration, Duration),
+    /// The pulse is longer than the maximum width
+    TooLong(Duration, Duration),
+    /// The signal changed several times at the same timestamp
+    Collision,
+}
+
+/// Pulse that does not fulfill a `PulseCheck`
+#[derive(Debug, Clone)]
+pub struct Violation {
+    /// Identifier of the signal
+    pub signal_id: String,
+    /// Time period of the pulse
+    pub period: TimeDescr,
+    /// Value of the signal during the pulse
+    pub value: SignalValue,
+    /// Kind of violation
+    pub kind: ViolationKind,
+}
+
+/// Result of pulse-width checks
+#[derive(Debug, Default)]
+pub struct PulseReport {
+    violations: Vec<Violation>,
+}
+
+impl PulseCheck {
+    /// Create a new `PulseCheck` on a signal referenced by its identifier, name or hierarchical
+    /// name. If `value` is given, only the pulses where the signal holds that value are checked.
+    pub fn new(
+        signal: &str,
+        value: Option<SignalValue>,
+        min: Option<Duration>,
+        max: Option<Duration>,
+    ) -> PulseCheck {
+        PulseCheck {
+            signal: signal.to_string(),
+            value,
+            min,
+            max,
+        }
+    }
+
+    fn parse(spec: &str) -> Result<(&str, Option<SignalValue>, &str), AnalysisError> {
+        let (pulse, width) = spec
+            .rsplit_once(':')
+            .ok_or_else(|| AnalysisError::new(&format!("missing width in '{}'", spec)))?;
+        let (signal, value) = match pulse.split_once('=') {
+            Some((signal, value)) => (signal.trim(), Some(parse_value(value)?)),
+            None => (pulse.trim(), None),
+        };
+        Ok((signal, value, width))
+    }
+
+    /// Parse a minimum pulse-width check with the format `SIGNAL[=VALUE]:WIDTH`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::PulseCheck;
+    /// use dwfv::signaldb::SignalDB;
+    /// let db = SignalDB::new();
+    ///
+    /// assert!(PulseCheck::parse_min(&db, "reset=1:10 cycles").is_ok());
+    /// assert!(PulseCheck::parse_min(&db, "clk:5").is_ok());
+    /// assert!(PulseCheck::parse_min(&db, "reset=1").is_err());
+    /// ```
+    pub fn parse_min(signaldb: &SignalDB, spec: &str) -> Result<PulseCheck, AnalysisError> {
+        let (signal, value, width) = PulseCheck::parse(spec)?;
+        let min = Duration::parse(signaldb, width)?;
+        Ok(PulseCheck::new(signal, value, Some(min), None))
+    }
+
+    /// Parse a maximum pulse-width check with the format `SIGNAL[=VALUE]:WIDTH`.
+    ///
+    /// # Example
+    ///
+    /// See [`parse_min`].
+    ///
+    /// [`parse_min`]: #method.parse_min
+    pub fn parse_max(signaldb: &SignalDB, spec: &str) -> Result<PulseCheck, AnalysisError> {
+        let (signal, value, width) = PulseCheck::parse(spec)?;
+        let max = Duration::parse(signaldb, width)?;
+        Ok(PulseCheck::new(signal, value, None, Some(max)))
+    }
+
+    fn uses_cycles(&self) -> bool {
+        matches!(self.min, Some(Duration::Cycles(_)))
+            || matches!(self.max, Some(Duration::Cycles(_)))
+    }
+
+    fn run(&self, signal: &Signal, clock: Option<&Signal>, end: Timestamp) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let mut events = signal.events().enumerate().peekable();
+
+        while let Some((i, (begin, value))) = events.next() {
+            let next = events.peek().map(|(_, (t, _))| *t);
+            if let Some(expected) = &self.value {
+                if value != expected {
+                    continue;
+                }
+            } else if i == 0 && value.is_invalid() {
+                continue;
+            }
+
+            let period_end = next.unwrap_or(end);
+            let measure = |bound: &Duration| match (bound, clock) {
+                (Duration::Cycles(_), Some(clock)) => {
+                    Duration::Cycles(clock.count_rising_edges(begin, period_end))
+                }
+                _ => Duration::Time(period_end - begin),
+            };
+
+            let mut violation = None;
+            if let (Some(min), Some(_)) = (&self.min, next) {
+                let width = measure(min);
+                if width < *min {
+                    violation = Some(ViolationKind::TooShort(width, *min))
+                }
+            }
+            if let Some(max) = &self.max {
+                let width = measure(max);
+                if width > *max {
+                    violation = Some(ViolationKind::TooLong(width, *max))
+                }
+            }
+
+            if let Some(kind) = violation {
+                violations.push(Violation {
+                    signal_id: signal.id.clone(),
+                    period: TimeDescr::Period(begin, period_end),
+                    value: value.clone(),
+                    kind,
+                })
+            }
+        }
+        violations
+    }
+}
+
+impl PulseReport {
+    /// Look for pulses shorter than `threshold` and for simultaneous transitions in the signals
+    /// matching a glob pattern (or in all the signals if no pattern is given).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::PulseReport;
+    /// use dwfv::signaldb::{Scale, SignalDB, Timestamp};
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 foo $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// #10
+    /// 10
+    /// #11
+    /// 00
+    /// #30
+    /// 10
+    /// 00
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let report = PulseReport::glitches(&db, Some("foo"), Timestamp::new(2, Scale::Picosecond));
+    ///
+    /// let mut buf = Vec::new();
+    /// report.format(&db, &mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "0 (top.foo)\n  10ps-11ps: pulse of 1ps at h1, shorter than 2ps\n  \
+    ///     30ps: multiple transitions\n"
+    /// );
+    /// ```
+    pub fn glitches(
+        signaldb: &SignalDB,
+        pattern: Option<&str>,
+        threshold: Timestamp,
+    ) -> PulseReport {
+        let ids = select_signals(signaldb, pattern);
+        let end = signaldb.get_time();
+        let mut violations = Vec::new();
+        signaldb.with_signals(|signals| {
+            for id in &ids {
+                let signal = &signals[id];
+                let check = PulseCheck::new(id, None, Some(Duration::Time(threshold)), None);
+                let mut signal_violations = check.run(signal, None, end);
+                for t in signal.get_collisions() {
+                    signal_violations.push(Violation {
+                        signal_id: id.clone(),
+                        period: TimeDescr::Point(*t),
+                        value: signal.value_at(*t),
+                        kind: ViolationKind::Collision,
+                    })
+                }
+                signal_violations.sort_by_key(|v| match v.period {
+                    TimeDescr::Point(t) | TimeDescr::Period(t, _) => t,
+                });
+                violations.append(&mut signal_violations)
+            }
+        });
+        PulseReport { violations }
+    }
+
+    /// Run pulse-width checks. `clock` is required by the checks expressed in clock cycles.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{Duration, PulseCheck, PulseReport};
+    /// use dwfv::signaldb::{SignalDB, SignalValue};
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 clk $end
+    /// $var wire 1 1 reset $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// 11
+    /// #1
+    /// 10
+    /// #2
+    /// 00
+    /// #3
+    /// 10
+    /// #4
+    /// 00
+    /// 01
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let check = PulseCheck::parse_min(&db, "reset=1:10 cycles").unwrap();
+    /// let report = PulseReport::check(&db, &[check], Some("clk")).unwrap();
+    ///
+    /// let mut buf = Vec::new();
+    /// report.format(&db, &mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "1 (top.reset)\n  0ps-4ps: pulse of 2 cycles at h1, shorter than 10 cycles\n"
+    /// );
+    /// ```
+    pub fn check(
+        signaldb: &SignalDB,
+        checks: &[PulseCheck],
+        clock: Option<&str>,
+    ) -> Result<PulseReport, Box<dyn Error>> {
+        let end = signaldb.get_time();
+        signaldb.with_signals(|signals| {
+            let clock = match clock {
+                Some(clock) => Some(&signals[&resolve_signal(signals, clock)?]),
+                None => None,
+            };
+            let mut violations = Vec::new();
+            for check in checks {
+                if check.uses_cycles() && clock.is_none() {
+                    return Err(Box::new(AnalysisError::new(&format!(
+                        "a clock is required to check the pulses of '{}' in cycles",
+                        check.signal
+                    ))) as Box<dyn Error>);
+                }
+                let signal = &signals[&resolve_signal(signals, &check.signal)?];
+                violations.append(&mut check.run(signal, clock, end))
+            }
+            Ok(PulseReport { violations })
+        })
+    }
+
+    /// Get the violations found by the checks.
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// Get the time periods of the violations.
+    pub fn findings(&self) -> Vec<TimeDescr> {
+        self.violations.iter().map(|v| v.period).collect()
+    }
+
+    /// Format the violations, grouped by signal.
+    pub fn format(&self, signaldb: &SignalDB, output: &mut dyn io::Write) {
+        let mut current = None;
+        for violation in &self.violations {
+            if current != Some(&violation.signal_id) {
+                let name = signaldb
+                    .get_signal_fullname(&violation.signal_id)
+                    .unwrap_or_default();
+                let _ = writeln!(output, "{} ({})", violation.signal_id, name);
+                current = Some(&violation.signal_id)
+            }
+            let _ = writeln!(output, "  {}", violation);
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ViolationKind::TooShort(width, min) => write!(
+                f,
+                "{}: pulse of {} at {}, shorter than {}",
+                self.period, width, self.value, min
+            ),
+            ViolationKind::TooLong(width, max) => write!(
+                f,
+                "{}: pulse of {} at {}, longer than {}",
+                self.period, width, self.value, max
+            ),
+            ViolationKind::Collision => write!(f, "{}: multiple transitions", self.period),
+        }
+    }
+}
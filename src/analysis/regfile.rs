@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: MIT
+use super::{AnalysisError, Memory, WritePort};
+use crate::signaldb::{Signal, SignalDB, SignalValue, Timestamp};
+use std::error::Error;
+
+/// Number of architectural integer registers of a RISC-V core
+pub const REGISTER_COUNT: u64 = 32;
+
+/// Reconstruct the architectural registers of a core from its writeback port, and add them to the
+/// `SignalDB` as signals `x0` to `x31` declared in `scope`. The writeback enable, the destination
+/// register and the data are sampled just before each rising edge of the clock, the registers
+/// being updated on the edge. `x0` is hardwired to zero and the other registers are unknown until
+/// their first write.
+///
+/// The identifiers of the new signals are returned.
+///
+/// # Example
+///
+/// ```
+/// use dwfv::analysis::{WritePort, shadow_registers};
+/// use dwfv::signaldb::{Scale, SignalDB, SignalValue, Timestamp};
+/// let vcd = std::io::Cursor::new("$scope module top $end
+/// $var wire 1 0 clk $end
+/// $var wire 1 1 wb_en $end
+/// $var wire 5 2 wb_rd $end
+/// $var wire 32 3 wb_data $end
+/// $upscope $end
+/// $enddefinitions $end
+/// #0
+/// 00
+/// 11
+/// b01010 2
+/// b101010 3
+/// #10
+/// 10
+/// b0 3
+/// #15
+/// 00
+/// #20
+/// 10
+/// #30
+/// ");
+///
+/// let db = SignalDB::from_vcd(vcd).unwrap();
+/// let port = WritePort::parse("clk,wb_en,wb_rd,wb_data").unwrap();
+/// let ids = shadow_registers(&db, &port, "regfile").unwrap();
+/// assert_eq!(ids.len(), 32);
+///
+/// let t = |v| Timestamp::new(v, Scale::Picosecond);
+/// assert_eq!(db.value_at("x10", t(5)).unwrap().is_invalid(), true);
+/// assert_eq!(db.value_at("x10", t(10)).unwrap(), SignalValue::new(42));
+/// assert_eq!(db.value_at("x10", t(20)).unwrap(), SignalValue::new(0));
+/// assert_eq!(db.value_at("x0", t(20)).unwrap(), SignalValue::new(0));
+/// assert_eq!(db.get_signal_fullname("x10").unwrap(), "regfile.x10[:32]");
+///
+/// // The registers can be searched like any other signal
+/// db.search("$x10 <- 0").unwrap();
+/// assert_eq!(db.get_first_finding("$x10 <- 0").unwrap(), Some(t(20)));
+/// ```
+pub fn shadow_registers(
+    signaldb: &SignalDB,
+    port: &WritePort,
+    scope: &str,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let ids = (0..REGISTER_COUNT)
+        .map(|i| format!("x{}", i))
+        .collect::<Vec<_>>();
+    if let Some(id) = ids.iter().find(|id| signaldb.signal_exists(id)) {
+        return Err(Box::new(AnalysisError::new(&format!(
+            "signal '{}' already exists",
+            id
+        ))));
+    }
+
+    let memory = Memory::reconstruct(signaldb, port)?;
+    let width = memory.width();
+    signaldb.create_scope(&[scope]);
+    for (index, id) in ids.iter().enumerate() {
+        let mut signal = Signal::new(id, id, width);
+        if index == 0 {
+            signal.add_event(Timestamp::origin(), SignalValue::new(0));
+        } else {
+            signal.add_event(Timestamp::origin(), SignalValue::invalid());
+            for write in memory.writes().iter().filter(|w| w.address == index as u64) {
+                signal.add_event(write.timestamp, write.data.clone())
+            }
+        }
+        signaldb.declare_signal(&[scope], signal)
+    }
+    Ok(ids)
+}
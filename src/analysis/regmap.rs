@@ -0,0 +1,679 @@
+// SPDX-License-Identifier: MIT
+use super::{AnalysisError, glob_match, parse_address, resolve_signal};
+use crate::signaldb::{Signal, SignalDB, SignalValue, Timestamp};
+use std::error::Error;
+use std::io;
+
+/// Bit field of a register
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    /// Name of the field
+    pub name: String,
+    /// Position of the least significant bit of the field
+    pub offset: usize,
+    /// Number of bits of the field
+    pub width: usize,
+}
+
+/// Memory-mapped register
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Register {
+    /// Name of the register, prefixed with the name of its peripheral (e.g. `UART0.CTRL`)
+    pub name: String,
+    /// Address of the register
+    pub address: u64,
+    /// Bit fields of the register
+    pub fields: Vec<Field>,
+}
+
+/// Registers of a memory map, loaded from a CMSIS-SVD file or from a CSV file
+#[derive(Debug, Clone, Default)]
+pub struct RegisterMap {
+    registers: Vec<Register>,
+}
+
+/// Signals of a bus whose accesses are decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BusPort {
+    /// Clock of the bus, sampled on its rising edges
+    pub clock: String,
+    /// Signal indicating an access, active high
+    pub valid: String,
+    /// Signal indicating a write access (read access when low)
+    pub write: String,
+    /// Address bus
+    pub address: String,
+    /// Data bus
+    pub data: String,
+}
+
+/// Access performed on a bus
+#[derive(Debug, Clone)]
+pub struct BusAccess {
+    /// Timestamp of the clock edge
+    pub timestamp: Timestamp,
+    /// Timestamp of the following clock edge, if any
+    pub next_edge: Option<Timestamp>,
+    /// Whether the access is a write
+    pub write: bool,
+    /// Accessed address, `None` if unknown
+    pub address: Option<u64>,
+    /// Written or read data
+    pub data: SignalValue,
+    /// Symbolic description of the access (e.g. `UART0.CTRL <= 0x13 (EN=1, TXIE=1)`)
+    pub description: String,
+}
+
+/// Accesses performed on a bus, decoded using a register map
+#[derive(Debug)]
+pub struct BusTrace {
+    map: RegisterMap,
+    accesses: Vec<BusAccess>,
+}
+
+/// Element of an XML document
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    fn text_of(&self, name: &str) -> Option<&str> {
+        self.child(name).map(|c| c.text.trim())
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Parse a (simplified) XML document: processing instructions, comments and DTDs are skipped,
+    /// and entities are not expanded.
+    fn parse(s: &str) -> Result<Element, AnalysisError> {
+        let invalid = || AnalysisError::new("invalid XML document");
+        let mut stack = vec![Element::default()];
+        let mut rest = s;
+        while let Some(start) = rest.find('<') {
+            stack.last_mut().unwrap().text.push_str(&rest[..start]);
+            rest = &rest[start..];
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                rest = &comment[comment.find("-->").ok_or_else(invalid)? + 3..];
+                continue;
+            }
+            let end = rest.find('>').ok_or_else(invalid)?;
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+            if tag.starts_with('?') || tag.starts_with('!') {
+                continue;
+            }
+            if let Some(name) = tag.strip_prefix('/') {
+                let element = stack.pop().ok_or_else(invalid)?;
+                if element.name != name.trim() || stack.is_empty() {
+                    return Err(invalid());
+                }
+                stack.last_mut().unwrap().children.push(element);
+                continue;
+            }
+
+            let self_closing = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let (name, mut attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            let mut element = Element {
+                name: name.to_string(),
+                ..Element::default()
+            };
+            while let Some((key, value)) = attrs.split_once('=') {
+                let value = value.trim_start();
+                let quote = value.chars().next().ok_or_else(invalid)?;
+                let close = value[1..].find(quote).ok_or_else(invalid)?;
+                element
+                    .attributes
+                    .push((key.trim().to_string(), value[1..close + 1].to_string()));
+                attrs = &value[close + 2..];
+            }
+            if self_closing {
+                stack.last_mut().unwrap().children.push(element)
+            } else {
+                stack.push(element)
+            }
+        }
+        match stack.pop() {
+            Some(document) if stack.is_empty() => Ok(document),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+fn parse_number(s: &str) -> Result<u64, AnalysisError> {
+    let s = s.trim();
+    let value = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    };
+    value.ok_or_else(|| AnalysisError::new(&format!("invalid number '{}'", s)))
+}
+
+fn parse_svd_field(field: &Element) -> Result<Field, AnalysisError> {
+    let name = field.text_of("name").unwrap_or_default().to_string();
+    let invalid = || AnalysisError::new(&format!("invalid bit range for field '{}'", name));
+    let bit_range = |msb: &str, lsb: &str| {
+        let lsb = parse_number(lsb)?;
+        let width = parse_number(msb)?.checked_sub(lsb).ok_or_else(invalid)?;
+        Ok::<_, AnalysisError>((lsb, width + 1))
+    };
+    let (offset, width) = if let Some(offset) = field.text_of("bitOffset") {
+        let width = field.text_of("bitWidth").map(parse_number).transpose()?;
+        (parse_number(offset)?, width.unwrap_or(1))
+    } else if let (Some(lsb), Some(msb)) = (field.text_of("lsb"), field.text_of("msb")) {
+        bit_range(msb, lsb)?
+    } else if let Some(range) = field.text_of("bitRange") {
+        let (msb, lsb) = range
+            .trim_matches(['[', ']'])
+            .split_once(':')
+            .ok_or_else(|| AnalysisError::new(&format!("invalid bit range '{}'", range)))?;
+        bit_range(msb, lsb)?
+    } else {
+        return Err(AnalysisError::new(&format!(
+            "no bit position for field '{}'",
+            name
+        )));
+    };
+    // Registers are at most 64-bit wide.
+    if offset.checked_add(width).is_none_or(|end| end > 64) {
+        return Err(invalid());
+    }
+    Ok(Field {
+        name,
+        offset: offset as usize,
+        width: width as usize,
+    })
+}
+
+fn parse_svd_registers(
+    peripheral: &Element,
+    prefix: &str,
+    base: u64,
+    registers: &mut Vec<Register>,
+) -> Result<(), AnalysisError> {
+    let Some(list) = peripheral.child("registers") else {
+        return Ok(());
+    };
+    for register in list.children("register") {
+        let name = register.text_of("name").unwrap_or_default();
+        let offset = parse_number(register.text_of("addressOffset").unwrap_or("0"))?;
+        let fields = match register.child("fields") {
+            Some(fields) => fields
+                .children("field")
+                .map(parse_svd_field)
+                .collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+        let dim = register.text_of("dim").map(parse_number).transpose()?;
+        match dim {
+            Some(dim) if name.contains("%s") => {
+                let increment = parse_number(register.text_of("dimIncrement").unwrap_or("0"))?;
+                for i in 0..dim {
+                    registers.push(Register {
+                        name: format!("{}{}", prefix, name.replace("%s", &i.to_string())),
+                        address: base + offset + i * increment,
+                        fields: fields.clone(),
+                    })
+                }
+            }
+            _ => registers.push(Register {
+                name: format!("{}{}", prefix, name),
+                address: base + offset,
+                fields,
+            }),
+        }
+    }
+    Ok(())
+}
+
+impl RegisterMap {
+    /// Parse a register map, either in the CMSIS-SVD format (if it starts with `<`) or in the
+    /// CSV format (see [`RegisterMap::parse_csv`]).
+    pub fn parse(s: &str) -> Result<RegisterMap, AnalysisError> {
+        if s.trim_start().starts_with('<') {
+            RegisterMap::parse_svd(s)
+        } else {
+            RegisterMap::parse_csv(s)
+        }
+    }
+
+    /// Parse a register map with one `REGISTER,ADDRESS` or `REGISTER,ADDRESS,FIELD,LSB,WIDTH`
+    /// line per register or field. The lines starting with `#` and a `register,address,...`
+    /// header are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::RegisterMap;
+    /// let map = RegisterMap::parse_csv("register,address,field,lsb,width
+    /// UART0.CTRL,0x40000000,EN,0,1
+    /// UART0.CTRL,0x40000000,TXIE,4,1
+    /// UART0.DATA,0x40000004
+    /// ").unwrap();
+    ///
+    /// let ctrl = map.get(0x40000000).unwrap();
+    /// assert_eq!(ctrl.name, "UART0.CTRL");
+    /// assert_eq!(ctrl.fields.len(), 2);
+    /// assert_eq!(map.registers().len(), 2);
+    /// ```
+    pub fn parse_csv(s: &str) -> Result<RegisterMap, AnalysisError> {
+        let mut map = RegisterMap::default();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let columns = line.split(',').map(str::trim).collect::<Vec<_>>();
+            if columns
+                .get(1)
+                .is_some_and(|c| c.eq_ignore_ascii_case("address"))
+            {
+                continue;
+            }
+            let (name, address, field) = match columns[..] {
+                [name, address] => (name, address, None),
+                [name, address, field, lsb, width] => {
+                    let number = |s: &str| {
+                        s.parse::<usize>()
+                            .map_err(|_| AnalysisError::new(&format!("invalid bit '{}'", s)))
+                    };
+                    let field = Field {
+                        name: field.to_string(),
+                        offset: number(lsb)?,
+                        width: number(width)?,
+                    };
+                    (name, address, Some(field))
+                }
+                _ => {
+                    return Err(AnalysisError::new(&format!(
+                        "expected REGISTER,ADDRESS[,FIELD,LSB,WIDTH], got '{}'",
+                        line
+                    )));
+                }
+            };
+            let address = parse_address(address)?;
+            let index = match map.registers.iter().position(|r| r.name == name) {
+                Some(index) => index,
+                None => {
+                    map.registers.push(Register {
+                        name: name.to_string(),
+                        address,
+                        fields: Vec::new(),
+                    });
+                    map.registers.len() - 1
+                }
+            };
+            if let Some(field) = field {
+                map.registers[index].fields.push(field)
+            }
+        }
+        Ok(map)
+    }
+
+    /// Parse a register map in the CMSIS-SVD format. Peripherals derived from other peripherals
+    /// and register arrays are supported, register clusters are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::RegisterMap;
+    /// let map = RegisterMap::parse_svd("<?xml version=\"1.0\"?>
+    /// <device>
+    ///   <peripherals>
+    ///     <peripheral>
+    ///       <name>UART0</name>
+    ///       <baseAddress>0x40000000</baseAddress>
+    ///       <registers>
+    ///         <register>
+    ///           <name>CTRL</name>
+    ///           <addressOffset>0x0</addressOffset>
+    ///           <fields>
+    ///             <field><name>EN</name><bitOffset>0</bitOffset><bitWidth>1</bitWidth></field>
+    ///             <field><name>BAUD</name><bitRange>[15:8]</bitRange></field>
+    ///           </fields>
+    ///         </register>
+    ///       </registers>
+    ///     </peripheral>
+    ///     <peripheral derivedFrom=\"UART0\">
+    ///       <name>UART1</name>
+    ///       <baseAddress>0x40001000</baseAddress>
+    ///     </peripheral>
+    ///   </peripherals>
+    /// </device>").unwrap();
+    ///
+    /// let ctrl = map.get(0x40001000).unwrap();
+    /// assert_eq!(ctrl.name, "UART1.CTRL");
+    /// assert_eq!(ctrl.fields[1].offset, 8);
+    /// assert_eq!(ctrl.fields[1].width, 8);
+    ///
+    /// let field = |bits: &str| {
+    ///     RegisterMap::parse_svd(&format!(
+    ///         "<device><peripherals><peripheral><name>P</name><registers><register>\
+    ///         <name>R</name><addressOffset>0</addressOffset><fields><field><name>F</name>\
+    ///         {}</field></fields></register></registers></peripheral></peripherals></device>",
+    ///         bits
+    ///     ))
+    /// };
+    /// assert!(field("<bitRange>[63:0]</bitRange>").is_ok());
+    /// assert!(field("<bitRange>[3:8]</bitRange>").is_err());
+    /// assert!(field("<lsb>4</lsb><msb>2</msb>").is_err());
+    /// assert!(field("<bitOffset>60</bitOffset><bitWidth>8</bitWidth>").is_err());
+    /// ```
+    pub fn parse_svd(s: &str) -> Result<RegisterMap, AnalysisError> {
+        let document = Element::parse(s)?;
+        let device = document
+            .child("device")
+            .ok_or_else(|| AnalysisError::new("no device in SVD file"))?;
+        let mut registers = Vec::new();
+        let Some(peripherals) = device.child("peripherals") else {
+            return Ok(RegisterMap { registers });
+        };
+        for peripheral in peripherals.children("peripheral") {
+            let name = peripheral.text_of("name").unwrap_or_default();
+            let base = parse_number(peripheral.text_of("baseAddress").unwrap_or("0"))?;
+            let prefix = format!("{}.", name);
+            let parent = peripheral.attribute("derivedFrom").and_then(|parent| {
+                peripherals
+                    .children("peripheral")
+                    .find(|p| p.text_of("name") == Some(parent))
+            });
+            match parent {
+                Some(parent) if peripheral.child("registers").is_none() => {
+                    parse_svd_registers(parent, &prefix, base, &mut registers)?
+                }
+                _ => parse_svd_registers(peripheral, &prefix, base, &mut registers)?,
+            }
+        }
+        Ok(RegisterMap { registers })
+    }
+
+    /// Get the registers of the map.
+    pub fn registers(&self) -> &[Register] {
+        &self.registers
+    }
+
+    /// Get the register mapped at an address.
+    pub fn get(&self, address: u64) -> Option<&Register> {
+        self.registers.iter().find(|r| r.address == address)
+    }
+
+    /// Describe an access to an address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::RegisterMap;
+    /// use dwfv::signaldb::SignalValue;
+    /// let map = RegisterMap::parse_csv("UART0.CTRL,0x40000000,EN,0,1
+    /// UART0.CTRL,0x40000000,TXIE,4,1
+    /// UART0.CTRL,0x40000000,MODE,1,2
+    /// ").unwrap();
+    ///
+    /// assert_eq!(
+    ///     map.describe(Some(0x40000000), &SignalValue::new(0x13), true),
+    ///     "UART0.CTRL <= 0x13 (EN=1, TXIE=1, MODE=1)"
+    /// );
+    /// assert_eq!(map.describe(Some(0x40000008), &SignalValue::new(0x13), false), "0x40000008 => 0x13");
+    /// ```
+    pub fn describe(&self, address: Option<u64>, data: &SignalValue, write: bool) -> String {
+        let register = address.and_then(|a| self.get(a));
+        let target = match (register, address) {
+            (Some(register), _) => register.name.clone(),
+            (None, Some(address)) => format!("0x{:x}", address),
+            (None, None) => "?".to_string(),
+        };
+        let arrow = if write { "<=" } else { "=>" };
+        let Some(value) = data.to_u64() else {
+            return format!("{} {} {}", target, arrow, data);
+        };
+
+        let mut description = format!("{} {} 0x{:x}", target, arrow, value);
+        let fields = register.map(|r| &r.fields[..]).unwrap_or_default();
+        if !fields.is_empty() {
+            let fields = fields
+                .iter()
+                .map(|field| {
+                    let mask = if field.width >= 64 {
+                        u64::MAX
+                    } else {
+                        (1 << field.width) - 1
+                    };
+                    let v = value.checked_shr(field.offset as u32).unwrap_or(0) & mask;
+                    if v < 10 {
+                        format!("{}={}", field.name, v)
+                    } else {
+                        format!("{}=0x{:x}", field.name, v)
+                    }
+                })
+                .collect::<Vec<_>>();
+            description.push_str(&format!(" ({})", fields.join(", ")))
+        }
+        description
+    }
+}
+
+impl BusPort {
+    /// Parse a bus port with the format `CLK,VALID,WRITE,ADDR,DATA`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::BusPort;
+    /// let port = BusPort::parse("clk,psel,pwrite,paddr,pwdata").unwrap();
+    /// assert_eq!(port.write, "pwrite");
+    /// assert!(BusPort::parse("clk,psel").is_err());
+    /// ```
+    pub fn parse(spec: &str) -> Result<BusPort, AnalysisError> {
+        match spec.split(',').map(str::trim).collect::<Vec<_>>()[..] {
+            [clock, valid, write, address, data] => Ok(BusPort {
+                clock: clock.to_string(),
+                valid: valid.to_string(),
+                write: write.to_string(),
+                address: address.to_string(),
+                data: data.to_string(),
+            }),
+            _ => Err(AnalysisError::new(&format!(
+                "expected CLK,VALID,WRITE,ADDR,DATA, got '{}'",
+                spec
+            ))),
+        }
+    }
+}
+
+impl BusAccess {
+    /// Check if the accessed register or one of its fields matches a glob pattern. The accesses
+    /// to unmapped addresses are matched against their hexadecimal address (e.g. `0x4000*`), and
+    /// the ones to unknown addresses against `?`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{BusAccess, RegisterMap};
+    /// use dwfv::signaldb::{SignalValue, Timestamp};
+    /// let map = RegisterMap::parse_csv("UART0.CTRL,0x40000000,EN,0,1").unwrap();
+    /// let access = |address| BusAccess {
+    ///     timestamp: Timestamp::origin(),
+    ///     next_edge: None,
+    ///     write: true,
+    ///     address,
+    ///     data: SignalValue::new(1),
+    ///     description: String::new(),
+    /// };
+    ///
+    /// assert!(access(Some(0x40000000)).matches(&map, "UART0.*"));
+    /// assert!(access(Some(0x40000000)).matches(&map, "*.EN"));
+    /// assert!(access(Some(0x40001000)).matches(&map, "0x4000*"));
+    /// assert!(!access(Some(0x40001000)).matches(&map, "?"));
+    /// assert!(access(None).matches(&map, "?"));
+    /// ```
+    pub fn matches(&self, map: &RegisterMap, pattern: &str) -> bool {
+        match self.address.and_then(|a| map.get(a)) {
+            Some(register) => {
+                glob_match(pattern, &register.name)
+                    || register.fields.iter().any(|f| {
+                        glob_match(pattern, &f.name)
+                            || glob_match(pattern, &format!("{}.{}", register.name, f.name))
+                    })
+            }
+            None => match self.address {
+                Some(address) => glob_match(pattern, &format!("0x{:x}", address)),
+                None => glob_match(pattern, "?"),
+            },
+        }
+    }
+}
+
+impl BusTrace {
+    /// Decode the accesses performed on a bus. The signals of the bus are sampled just before
+    /// each rising edge of the clock, an access being performed when `valid` is high.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{BusPort, BusTrace, RegisterMap};
+    /// use dwfv::signaldb::{Scale, SignalDB, SignalValue, Timestamp};
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 clk $end
+    /// $var wire 1 1 valid $end
+    /// $var wire 1 2 write $end
+    /// $var wire 8 3 addr $end
+    /// $var wire 8 4 data $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// 11
+    /// 12
+    /// b0 3
+    /// b10011 4
+    /// #10
+    /// 10
+    /// 02
+    /// b100 3
+    /// b1 4
+    /// #15
+    /// 00
+    /// #20
+    /// 10
+    /// 01
+    /// #25
+    /// 00
+    /// #30
+    /// 10
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let map = RegisterMap::parse_csv("UART0.CTRL,0,EN,0,1\nUART0.STATUS,4,READY,0,1").unwrap();
+    /// let trace = BusTrace::decode(&db, &BusPort::parse("clk,valid,write,addr,data").unwrap(), map)
+    ///     .unwrap();
+    ///
+    /// let mut buf = Vec::new();
+    /// trace.format(Some("READY"), &mut buf);
+    /// assert_eq!(String::from_utf8(buf).unwrap(), "20ps: UART0.STATUS => 0x1 (READY=1)\n");
+    ///
+    /// trace.declare_signal(&db, "bus").unwrap();
+    /// let t = |v| Timestamp::new(v, Scale::Picosecond);
+    /// assert_eq!(
+    ///     db.value_at("bus", t(10)).unwrap(),
+    ///     SignalValue::from_symbol_str("UART0.CTRL <= 0x13 (EN=1)")
+    /// );
+    /// assert_eq!(db.value_at("bus", t(30)).unwrap(), SignalValue::from_symbol_str(""));
+    /// ```
+    pub fn decode(
+        signaldb: &SignalDB,
+        port: &BusPort,
+        map: RegisterMap,
+    ) -> Result<BusTrace, Box<dyn Error>> {
+        signaldb.with_signals(|signals| {
+            let clock = &signals[&resolve_signal(signals, &port.clock)?];
+            let valid = &signals[&resolve_signal(signals, &port.valid)?];
+            let write = &signals[&resolve_signal(signals, &port.write)?];
+            let address = &signals[&resolve_signal(signals, &port.address)?];
+            let data = &signals[&resolve_signal(signals, &port.data)?];
+
+            let mut accesses = Vec::new();
+            let mut edge = clock
+                .get_first_event()
+                .and_then(|t| clock.get_next_rising_edge(t - t.derive(1)));
+            while let Some(timestamp) = edge {
+                let sample = timestamp - timestamp.derive(1);
+                let next_edge = clock.get_next_rising_edge(timestamp);
+                if valid.value_at(sample).to_u64() == Some(1) {
+                    let write = write.value_at(sample).to_u64() == Some(1);
+                    let address = address.value_at(sample).to_u64();
+                    let data = data.value_at(sample);
+                    accesses.push(BusAccess {
+                        timestamp,
+                        next_edge,
+                        write,
+                        address,
+                        description: map.describe(address, &data, write),
+                        data,
+                    })
+                }
+                edge = next_edge
+            }
+            Ok(BusTrace { map, accesses })
+        })
+    }
+
+    /// Get the decoded accesses.
+    pub fn accesses(&self) -> &[BusAccess] {
+        &self.accesses
+    }
+
+    /// Add the decoded accesses to the `SignalDB` as a symbolic signal, declared in the
+    /// `regmap` scope. The signal holds the description of each access until the next clock
+    /// edge, and an empty string in between accesses.
+    pub fn declare_signal(&self, signaldb: &SignalDB, id: &str) -> Result<(), AnalysisError> {
+        if signaldb.signal_exists(id) {
+            return Err(AnalysisError::new(&format!(
+                "signal '{}' already exists",
+                id
+            )));
+        }
+        let mut signal = Signal::new(id, id, 1);
+        signal.add_event(Timestamp::origin(), SignalValue::from_symbol_str(""));
+        for (i, access) in self.accesses.iter().enumerate() {
+            signal.add_event(
+                access.timestamp,
+                SignalValue::from_symbol_str(&access.description),
+            );
+            let next = self.accesses.get(i + 1).map(|a| a.timestamp);
+            if let Some(end) = access.next_edge
+                && next != Some(end)
+            {
+                signal.add_event(end, SignalValue::from_symbol_str(""))
+            }
+        }
+        signaldb.create_scope(&["regmap"]);
+        signaldb.declare_signal(&["regmap"], signal);
+        Ok(())
+    }
+
+    /// Format the decoded accesses whose register or field matches a glob pattern (or all of them
+    /// if no pattern is given).
+    pub fn format(&self, pattern: Option<&str>, output: &mut dyn io::Write) {
+        for access in &self.accesses {
+            if pattern.is_none_or(|p| access.matches(&self.map, p)) {
+                let _ = writeln!(output, "{}: {}", access.timestamp, access.description);
+            }
+        }
+    }
+}
@@ -0,0 +1,612 @@
+// SPDX-License-Identifier: MIT
+use super::{AnalysisError, resolve_signal};
+use crate::signaldb::{SignalDB, SignalValue, Timestamp};
+use std::error::Error;
+use std::io;
+
+const REGISTERS: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+/// Width of the integer registers of a RISC-V core
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Xlen {
+    /// RV32
+    Rv32,
+    /// RV64
+    Rv64,
+}
+
+/// Instruction retired by a core
+#[derive(Debug, Clone)]
+pub struct TracedInstruction {
+    /// Timestamp of the clock edge
+    pub timestamp: Timestamp,
+    /// Program counter
+    pub pc: SignalValue,
+    /// Instruction word
+    pub instruction: SignalValue,
+}
+
+/// Instructions retired by a core, sampled on the rising edges of its clock
+#[derive(Debug)]
+pub struct InstructionTrace {
+    xlen: Xlen,
+    instructions: Vec<TracedInstruction>,
+}
+
+fn bits(word: u32, high: u32, low: u32) -> u32 {
+    (word >> low) & ((1 << (high - low + 1)) - 1)
+}
+
+fn sign_extend(value: u32, width: u32) -> i32 {
+    ((value << (32 - width)) as i32) >> (32 - width)
+}
+
+fn reg(index: u32) -> &'static str {
+    REGISTERS[index as usize & 0x1f]
+}
+
+/// Register of the compressed instructions (`x8` to `x15`)
+fn creg(index: u32) -> &'static str {
+    REGISTERS[8 + (index as usize & 0x7)]
+}
+
+fn disassemble_32(insn: u32, xlen: Xlen) -> Option<String> {
+    let rv64 = xlen == Xlen::Rv64;
+    let rd = bits(insn, 11, 7);
+    let rs1 = bits(insn, 19, 15);
+    let rs2 = bits(insn, 24, 20);
+    let funct3 = bits(insn, 14, 12);
+    let funct7 = bits(insn, 31, 25);
+    let imm_i = sign_extend(bits(insn, 31, 20), 12);
+    let imm_s = sign_extend((bits(insn, 31, 25) << 5) | bits(insn, 11, 7), 12);
+    let imm_b = sign_extend(
+        (bits(insn, 31, 31) << 12)
+            | (bits(insn, 7, 7) << 11)
+            | (bits(insn, 30, 25) << 5)
+            | (bits(insn, 11, 8) << 1),
+        13,
+    );
+    let imm_j = sign_extend(
+        (bits(insn, 31, 31) << 20)
+            | (bits(insn, 19, 12) << 12)
+            | (bits(insn, 20, 20) << 11)
+            | (bits(insn, 30, 21) << 1),
+        21,
+    );
+
+    let s = match bits(insn, 6, 0) {
+        0x37 => format!("lui {}, 0x{:x}", reg(rd), insn >> 12),
+        0x17 => format!("auipc {}, 0x{:x}", reg(rd), insn >> 12),
+        0x6f => match rd {
+            0 => format!("j {}", imm_j),
+            _ => format!("jal {}, {}", reg(rd), imm_j),
+        },
+        0x67 if funct3 == 0 => match (rd, rs1, imm_i) {
+            (0, 1, 0) => "ret".to_string(),
+            (0, _, 0) => format!("jr {}", reg(rs1)),
+            _ => format!("jalr {}, {}({})", reg(rd), imm_i, reg(rs1)),
+        },
+        0x63 => {
+            let op = ["beq", "bne", "", "", "blt", "bge", "bltu", "bgeu"][funct3 as usize];
+            if op.is_empty() {
+                return None;
+            }
+            format!("{} {}, {}, {}", op, reg(rs1), reg(rs2), imm_b)
+        }
+        0x03 => {
+            let op = match (funct3, rv64) {
+                (0, _) => "lb",
+                (1, _) => "lh",
+                (2, _) => "lw",
+                (3, true) => "ld",
+                (4, _) => "lbu",
+                (5, _) => "lhu",
+                (6, true) => "lwu",
+                _ => return None,
+            };
+            format!("{} {}, {}({})", op, reg(rd), imm_i, reg(rs1))
+        }
+        0x23 => {
+            let op = match (funct3, rv64) {
+                (0, _) => "sb",
+                (1, _) => "sh",
+                (2, _) => "sw",
+                (3, true) => "sd",
+                _ => return None,
+            };
+            format!("{} {}, {}({})", op, reg(rs2), imm_s, reg(rs1))
+        }
+        0x13 => {
+            let shamt_width = if rv64 { 6 } else { 5 };
+            let shamt = bits(insn, 19 + shamt_width, 20);
+            let shift_funct = bits(insn, 31, 20 + shamt_width);
+            match funct3 {
+                0 => match (rd, rs1, imm_i) {
+                    (0, 0, 0) => "nop".to_string(),
+                    (_, 0, _) => format!("li {}, {}", reg(rd), imm_i),
+                    (_, _, 0) => format!("mv {}, {}", reg(rd), reg(rs1)),
+                    _ => format!("addi {}, {}, {}", reg(rd), reg(rs1), imm_i),
+                },
+                1 if shift_funct == 0 => format!("slli {}, {}, {}", reg(rd), reg(rs1), shamt),
+                5 if shift_funct == 0 => format!("srli {}, {}, {}", reg(rd), reg(rs1), shamt),
+                5 if shift_funct == 0x20 >> (shamt_width - 5) => {
+                    format!("srai {}, {}, {}", reg(rd), reg(rs1), shamt)
+                }
+                2 | 3 | 4 | 6 | 7 => {
+                    let op = ["", "", "slti", "sltiu", "xori", "", "ori", "andi"][funct3 as usize];
+                    format!("{} {}, {}, {}", op, reg(rd), reg(rs1), imm_i)
+                }
+                _ => return None,
+            }
+        }
+        0x1b if rv64 => match (funct3, funct7) {
+            (0, _) => format!("addiw {}, {}, {}", reg(rd), reg(rs1), imm_i),
+            (1, 0) => format!("slliw {}, {}, {}", reg(rd), reg(rs1), rs2),
+            (5, 0) => format!("srliw {}, {}, {}", reg(rd), reg(rs1), rs2),
+            (5, 0x20) => format!("sraiw {}, {}, {}", reg(rd), reg(rs1), rs2),
+            _ => return None,
+        },
+        0x33 => {
+            let op = match (funct7, funct3) {
+                (0, 0) => "add",
+                (0x20, 0) => "sub",
+                (0, 1) => "sll",
+                (0, 2) => "slt",
+                (0, 3) => "sltu",
+                (0, 4) => "xor",
+                (0, 5) => "srl",
+                (0x20, 5) => "sra",
+                (0, 6) => "or",
+                (0, 7) => "and",
+                (1, _) => [
+                    "mul", "mulh", "mulhsu", "mulhu", "div", "divu", "rem", "remu",
+                ][funct3 as usize],
+                _ => return None,
+            };
+            format!("{} {}, {}, {}", op, reg(rd), reg(rs1), reg(rs2))
+        }
+        0x3b if rv64 => {
+            let op = match (funct7, funct3) {
+                (0, 0) => "addw",
+                (0x20, 0) => "subw",
+                (0, 1) => "sllw",
+                (0, 5) => "srlw",
+                (0x20, 5) => "sraw",
+                (1, 0) => "mulw",
+                (1, 4) => "divw",
+                (1, 5) => "divuw",
+                (1, 6) => "remw",
+                (1, 7) => "remuw",
+                _ => return None,
+            };
+            format!("{} {}, {}, {}", op, reg(rd), reg(rs1), reg(rs2))
+        }
+        0x0f => match funct3 {
+            0 => "fence".to_string(),
+            1 => "fence.i".to_string(),
+            _ => return None,
+        },
+        0x73 => match funct3 {
+            0 => match insn {
+                0x0000_0073 => "ecall".to_string(),
+                0x0010_0073 => "ebreak".to_string(),
+                0x1020_0073 => "sret".to_string(),
+                0x3020_0073 => "mret".to_string(),
+                0x1050_0073 => "wfi".to_string(),
+                _ => return None,
+            },
+            4 => return None,
+            _ => {
+                let op = [
+                    "", "csrrw", "csrrs", "csrrc", "", "csrrwi", "csrrsi", "csrrci",
+                ][funct3 as usize];
+                let csr = bits(insn, 31, 20);
+                if funct3 < 4 {
+                    format!("{} {}, 0x{:x}, {}", op, reg(rd), csr, reg(rs1))
+                } else {
+                    format!("{} {}, 0x{:x}, {}", op, reg(rd), csr, rs1)
+                }
+            }
+        },
+        0x2f => {
+            let size = match (funct3, rv64) {
+                (2, _) => "w",
+                (3, true) => "d",
+                _ => return None,
+            };
+            let order = match bits(insn, 26, 25) {
+                0 => "",
+                1 => ".rl",
+                2 => ".aq",
+                _ => ".aqrl",
+            };
+            let op = match bits(insn, 31, 27) {
+                0x02 if rs2 == 0 => {
+                    return Some(format!("lr.{}{} {}, ({})", size, order, reg(rd), reg(rs1)));
+                }
+                0x03 => "sc",
+                0x01 => "amoswap",
+                0x00 => "amoadd",
+                0x04 => "amoxor",
+                0x0c => "amoand",
+                0x08 => "amoor",
+                0x10 => "amomin",
+                0x14 => "amomax",
+                0x18 => "amominu",
+                0x1c => "amomaxu",
+                _ => return None,
+            };
+            format!(
+                "{}.{}{} {}, {}, ({})",
+                op,
+                size,
+                order,
+                reg(rd),
+                reg(rs2),
+                reg(rs1)
+            )
+        }
+        _ => return None,
+    };
+    Some(s)
+}
+
+fn disassemble_16(insn: u32, xlen: Xlen) -> Option<String> {
+    let rv64 = xlen == Xlen::Rv64;
+    let rd = bits(insn, 11, 7);
+    let rs2 = bits(insn, 6, 2);
+    let rd_c = bits(insn, 4, 2);
+    let rs1_c = bits(insn, 9, 7);
+    let imm6 = sign_extend((bits(insn, 12, 12) << 5) | bits(insn, 6, 2), 6);
+    let shamt = (bits(insn, 12, 12) << 5) | bits(insn, 6, 2);
+
+    let s = match (bits(insn, 1, 0), bits(insn, 15, 13)) {
+        (0, 0) => {
+            let imm = (bits(insn, 12, 11) << 4)
+                | (bits(insn, 10, 7) << 6)
+                | (bits(insn, 6, 6) << 2)
+                | (bits(insn, 5, 5) << 3);
+            if imm == 0 {
+                return None;
+            }
+            format!("addi {}, sp, {}", creg(rd_c), imm)
+        }
+        (0, 2) | (0, 6) => {
+            let imm = (bits(insn, 12, 10) << 3) | (bits(insn, 6, 6) << 2) | (bits(insn, 5, 5) << 6);
+            let op = if bits(insn, 15, 13) == 2 { "lw" } else { "sw" };
+            format!("{} {}, {}({})", op, creg(rd_c), imm, creg(rs1_c))
+        }
+        (0, 3) | (0, 7) if rv64 => {
+            let imm = (bits(insn, 12, 10) << 3) | (bits(insn, 6, 5) << 6);
+            let op = if bits(insn, 15, 13) == 3 { "ld" } else { "sd" };
+            format!("{} {}, {}({})", op, creg(rd_c), imm, creg(rs1_c))
+        }
+        (1, 0) => match (rd, imm6) {
+            (0, _) => "nop".to_string(),
+            _ => format!("addi {}, {}, {}", reg(rd), reg(rd), imm6),
+        },
+        (1, 1) if rv64 => format!("addiw {}, {}, {}", reg(rd), reg(rd), imm6),
+        (1, 1) | (1, 5) => {
+            let imm = sign_extend(
+                (bits(insn, 12, 12) << 11)
+                    | (bits(insn, 11, 11) << 4)
+                    | (bits(insn, 10, 9) << 8)
+                    | (bits(insn, 8, 8) << 10)
+                    | (bits(insn, 7, 7) << 6)
+                    | (bits(insn, 6, 6) << 7)
+                    | (bits(insn, 5, 3) << 1)
+                    | (bits(insn, 2, 2) << 5),
+                12,
+            );
+            if bits(insn, 15, 13) == 1 {
+                format!("jal ra, {}", imm)
+            } else {
+                format!("j {}", imm)
+            }
+        }
+        (1, 2) => format!("li {}, {}", reg(rd), imm6),
+        (1, 3) if rd == 2 => {
+            let imm = sign_extend(
+                (bits(insn, 12, 12) << 9)
+                    | (bits(insn, 6, 6) << 4)
+                    | (bits(insn, 5, 5) << 6)
+                    | (bits(insn, 4, 3) << 7)
+                    | (bits(insn, 2, 2) << 5),
+                10,
+            );
+            if imm == 0 {
+                return None;
+            }
+            format!("addi sp, sp, {}", imm)
+        }
+        (1, 3) => {
+            if imm6 == 0 {
+                return None;
+            }
+            format!("lui {}, 0x{:x}", reg(rd), (imm6 as u32) & 0xfffff)
+        }
+        (1, 4) => match (bits(insn, 11, 10), bits(insn, 12, 12), bits(insn, 6, 5)) {
+            (0, _, _) => format!("srli {}, {}, {}", creg(rs1_c), creg(rs1_c), shamt),
+            (1, _, _) => format!("srai {}, {}, {}", creg(rs1_c), creg(rs1_c), shamt),
+            (2, _, _) => format!("andi {}, {}, {}", creg(rs1_c), creg(rs1_c), imm6),
+            (_, 0, funct2) => {
+                let op = ["sub", "xor", "or", "and"][funct2 as usize];
+                format!("{} {}, {}, {}", op, creg(rs1_c), creg(rs1_c), creg(rd_c))
+            }
+            (_, _, funct2) if rv64 && funct2 < 2 => {
+                let op = ["subw", "addw"][funct2 as usize];
+                format!("{} {}, {}, {}", op, creg(rs1_c), creg(rs1_c), creg(rd_c))
+            }
+            _ => return None,
+        },
+        (1, 6) | (1, 7) => {
+            let imm = sign_extend(
+                (bits(insn, 12, 12) << 8)
+                    | (bits(insn, 11, 10) << 3)
+                    | (bits(insn, 6, 5) << 6)
+                    | (bits(insn, 4, 3) << 1)
+                    | (bits(insn, 2, 2) << 5),
+                9,
+            );
+            let op = if bits(insn, 15, 13) == 6 {
+                "beqz"
+            } else {
+                "bnez"
+            };
+            format!("{} {}, {}", op, creg(rs1_c), imm)
+        }
+        (2, 0) => format!("slli {}, {}, {}", reg(rd), reg(rd), shamt),
+        (2, 2) if rd != 0 => {
+            let imm = (bits(insn, 12, 12) << 5) | (bits(insn, 6, 4) << 2) | (bits(insn, 3, 2) << 6);
+            format!("lw {}, {}(sp)", reg(rd), imm)
+        }
+        (2, 3) if rv64 && rd != 0 => {
+            let imm = (bits(insn, 12, 12) << 5) | (bits(insn, 6, 5) << 3) | (bits(insn, 4, 2) << 6);
+            format!("ld {}, {}(sp)", reg(rd), imm)
+        }
+        (2, 4) => match (bits(insn, 12, 12), rd, rs2) {
+            (0, 0, 0) => return None,
+            (0, 1, 0) => "ret".to_string(),
+            (0, _, 0) => format!("jr {}", reg(rd)),
+            (0, _, _) => format!("mv {}, {}", reg(rd), reg(rs2)),
+            (_, 0, 0) => "ebreak".to_string(),
+            (_, _, 0) => format!("jalr ra, 0({})", reg(rd)),
+            _ => format!("add {}, {}, {}", reg(rd), reg(rd), reg(rs2)),
+        },
+        (2, 6) => {
+            let imm = (bits(insn, 12, 9) << 2) | (bits(insn, 8, 7) << 6);
+            format!("sw {}, {}(sp)", reg(rs2), imm)
+        }
+        (2, 7) if rv64 => {
+            let imm = (bits(insn, 12, 10) << 3) | (bits(insn, 9, 7) << 6);
+            format!("sd {}, {}(sp)", reg(rs2), imm)
+        }
+        _ => return None,
+    };
+    Some(s)
+}
+
+/// Disassemble a RISC-V instruction of the RV32IMAC or RV64IMAC instruction sets. Compressed
+/// instructions are read from the 16 least significant bits of the word and are displayed as
+/// their expanded equivalent. Branch and jump targets are displayed as offsets relative to the
+/// instruction. `None` is returned for illegal or unsupported instructions.
+///
+/// # Example
+///
+/// ```
+/// use dwfv::analysis::{Xlen, disassemble};
+/// assert_eq!(disassemble(0x00150513, Xlen::Rv32).unwrap(), "addi a0, a0, 1");
+/// assert_eq!(disassemble(0xfe5ff06f, Xlen::Rv32).unwrap(), "j -28");
+/// assert_eq!(disassemble(0x02b50533, Xlen::Rv32).unwrap(), "mul a0, a0, a1");
+/// assert_eq!(disassemble(0x00813403, Xlen::Rv64).unwrap(), "ld s0, 8(sp)");
+/// assert_eq!(disassemble(0x00813403, Xlen::Rv32), None);
+/// assert_eq!(disassemble(0x0505, Xlen::Rv32).unwrap(), "addi a0, a0, 1");
+/// assert_eq!(disassemble(0x8082, Xlen::Rv32).unwrap(), "ret");
+/// ```
+pub fn disassemble(insn: u32, xlen: Xlen) -> Option<String> {
+    if insn & 0x3 == 0x3 {
+        disassemble_32(insn, xlen)
+    } else {
+        disassemble_16(insn & 0xffff, xlen)
+    }
+}
+
+impl Xlen {
+    /// Display a value as a RISC-V instruction. The value itself is displayed if it is not a
+    /// legal instruction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::Xlen;
+    /// use dwfv::signaldb::SignalValue;
+    /// assert_eq!(Xlen::Rv32.translate(&SignalValue::new(0x00150513)), "addi a0, a0, 1");
+    /// assert_eq!(Xlen::Rv32.translate(&SignalValue::new(0xffffffff)), "hFFFFFFFF");
+    /// ```
+    pub fn translate(self, value: &SignalValue) -> String {
+        value
+            .to_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .and_then(|insn| disassemble(insn, self))
+            .unwrap_or_else(|| value.to_string())
+    }
+}
+
+impl InstructionTrace {
+    /// Capture the instructions retired by a core, i.e. the program counter and the instruction
+    /// word sampled on each rising edge of `clock` on which the `valid` signal is high.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{InstructionTrace, Xlen};
+    /// use dwfv::signaldb::SignalDB;
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 clk $end
+    /// $var wire 1 1 valid $end
+    /// $var wire 32 2 pc $end
+    /// $var wire 32 3 insn $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// 11
+    /// b10000000 2
+    /// b00000000000101010000010100010011 3
+    /// #10
+    /// 10
+    /// b10000100 2
+    /// b1000000010000010 3
+    /// #15
+    /// 00
+    /// #20
+    /// 10
+    /// 01
+    /// #25
+    /// 00
+    /// #30
+    /// 10
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let trace = InstructionTrace::capture(&db, "clk", "valid", "pc", "insn", Xlen::Rv32, None, None)
+    ///     .unwrap();
+    ///
+    /// let mut buf = Vec::new();
+    /// trace.format(&mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "10ps 00000080: 00150513  addi a0, a0, 1\n\
+    ///     20ps 00000084: 00008082  ret\n"
+    /// );
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn capture(
+        signaldb: &SignalDB,
+        clock: &str,
+        valid: &str,
+        pc: &str,
+        instruction: &str,
+        xlen: Xlen,
+        begin: Option<Timestamp>,
+        end: Option<Timestamp>,
+    ) -> Result<InstructionTrace, Box<dyn Error>> {
+        let begin = begin.unwrap_or_else(Timestamp::origin);
+        let end = end.unwrap_or_else(|| signaldb.get_time());
+        signaldb.with_signals(|signals| {
+            let clock = &signals[&resolve_signal(signals, clock)?];
+            let valid = &signals[&resolve_signal(signals, valid)?];
+            let pc = &signals[&resolve_signal(signals, pc)?];
+            let instruction = &signals[&resolve_signal(signals, instruction)?];
+            if instruction.width > 32 {
+                return Err(Box::new(AnalysisError::new(&format!(
+                    "instruction signal '{}' is wider than 32 bits",
+                    instruction.get_fullname()
+                ))) as Box<dyn Error>);
+            }
+
+            let mut instructions = Vec::new();
+            let mut edge = clock.get_next_rising_edge(begin - begin.derive(1));
+            while let Some(timestamp) = edge {
+                if timestamp > end {
+                    break;
+                }
+                let sample = timestamp - timestamp.derive(1);
+                if valid.value_at(sample).to_u64() == Some(1) {
+                    instructions.push(TracedInstruction {
+                        timestamp,
+                        pc: pc.value_at(sample),
+                        instruction: instruction.value_at(sample),
+                    })
+                }
+                edge = clock.get_next_rising_edge(timestamp)
+            }
+            Ok(InstructionTrace { xlen, instructions })
+        })
+    }
+
+    /// Get the retired instructions.
+    pub fn instructions(&self) -> &[TracedInstruction] {
+        &self.instructions
+    }
+
+    /// Format one line per retired instruction with its timestamp, its program counter, its
+    /// instruction word and its disassembly.
+    pub fn format(&self, output: &mut dyn io::Write) {
+        let pc_digits = match self.xlen {
+            Xlen::Rv32 => 8,
+            Xlen::Rv64 => 16,
+        };
+        for traced in &self.instructions {
+            let pc = match traced.pc.to_u64() {
+                Some(pc) => format!("{:0digits$x}", pc, digits = pc_digits),
+                None => traced.pc.to_string(),
+            };
+            let word = match traced.instruction.to_u64() {
+                Some(word) => format!("{:08x}", word),
+                None => traced.instruction.to_string(),
+            };
+            let _ = writeln!(
+                output,
+                "{} {}: {}  {}",
+                traced.timestamp,
+                pc,
+                word,
+                self.xlen.translate(&traced.instruction)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rv32() {
+        let cases = [
+            (0x00000013, "nop"),
+            (0xfff00513, "li a0, -1"),
+            (0x00008067, "ret"),
+            (0x000780e7, "jalr ra, 0(a5)"),
+            (0x00b50463, "beq a0, a1, 8"),
+            (0xfea42623, "sw a0, -20(s0)"),
+            (0x40b50533, "sub a0, a0, a1"),
+            (0x4015d513, "srai a0, a1, 1"),
+            (0x100522af, "lr.w t0, (a0)"),
+            (0x00a5a2af, "amoadd.w t0, a0, (a1)"),
+            (0x34202573, "csrrs a0, 0x342, zero"),
+            (0x00000073, "ecall"),
+            (0x1141, "addi sp, sp, -16"),
+            (0xc606, "sw ra, 12(sp)"),
+            (0x4501, "li a0, 0"),
+            (0x852e, "mv a0, a1"),
+            (0xa001, "j 0"),
+            (0xc119, "beqz a0, 6"),
+            (0x9782, "jalr ra, 0(a5)"),
+        ];
+        for (insn, expected) in cases {
+            assert_eq!(disassemble(insn, Xlen::Rv32).unwrap(), expected);
+        }
+        assert_eq!(disassemble(0x0015051b, Xlen::Rv32), None);
+        assert_eq!(disassemble(0x0000, Xlen::Rv32), None);
+    }
+
+    #[test]
+    fn rv64() {
+        assert_eq!(
+            disassemble(0x0015051b, Xlen::Rv64).unwrap(),
+            "addiw a0, a0, 1"
+        );
+        assert_eq!(
+            disassemble(0x4205d513, Xlen::Rv64).unwrap(),
+            "srai a0, a1, 32"
+        );
+        assert_eq!(disassemble(0x2505, Xlen::Rv64).unwrap(), "addiw a0, a0, 1");
+        assert_eq!(disassemble(0x2505, Xlen::Rv32).unwrap(), "jal ra, 1568");
+    }
+}
@@ -0,0 +1,233 @@
+// SPDX-License-Identifier: MIT
+use super::{AnalysisError, check_window, select_signals};
+use crate::signaldb::{BitValue, Signal, SignalDB, SignalValue, Timestamp};
+use std::collections::BTreeMap;
+use std::io;
+
+/// Switching activity of a single bit
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct BitActivity {
+    /// Time spent at `0`, in units of the time scale
+    pub t0: usize,
+    /// Time spent at `1`, in units of the time scale
+    pub t1: usize,
+    /// Time spent at an unknown value, in units of the time scale
+    pub tx: usize,
+    /// Time spent at high impedance, in units of the time scale
+    pub tz: usize,
+    /// Number of `0 -> 1` and `1 -> 0` transitions
+    pub tc: usize,
+}
+
+#[derive(Debug, Default)]
+struct Instance {
+    nets: Vec<(String, BitActivity)>,
+    instances: BTreeMap<String, Instance>,
+}
+
+/// Switching activity of the signals of a `SignalDB`, following its scope hierarchy
+#[derive(Debug)]
+pub struct SwitchingActivity {
+    timescale: Timestamp,
+    duration: usize,
+    top: Instance,
+}
+
+impl BitActivity {
+    /// Compute the switching activity of each bit of a signal between `begin` and `end`. The
+    /// durations are expressed in units of `timescale`.
+    fn compute(
+        signal: &Signal,
+        begin: Timestamp,
+        end: Timestamp,
+        timescale: Timestamp,
+    ) -> Vec<BitActivity> {
+        let mut activity = vec![BitActivity::default(); signal.width];
+        let mut value = signal.value_at(begin);
+        let mut since = begin;
+
+        let mut account = |value: &SignalValue, next: Option<&SignalValue>, period: Timestamp| {
+            for (i, bit) in activity.iter_mut().enumerate() {
+                let state = value.get_bit(i);
+                let duration = period / timescale;
+                match state {
+                    BitValue::Low => bit.t0 += duration,
+                    BitValue::High => bit.t1 += duration,
+                    BitValue::HighZ => bit.tz += duration,
+                    _ => bit.tx += duration,
+                }
+                if let Some(next) = next {
+                    match (state, next.get_bit(i)) {
+                        (BitValue::Low, BitValue::High) | (BitValue::High, BitValue::Low) => {
+                            bit.tc += 1
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        };
+
+        for (timestamp, new_value) in signal.events() {
+            if timestamp <= begin {
+                continue;
+            } else if timestamp >= end {
+                break;
+            }
+            account(&value, Some(new_value), timestamp - since);
+            value = new_value.clone();
+            since = timestamp
+        }
+        account(&value, None, end - since);
+        activity
+    }
+}
+
+fn escape(name: &str) -> String {
+    let mut escaped = String::new();
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() && c != '_' {
+            escaped.push('\\')
+        }
+        escaped.push(c)
+    }
+    escaped
+}
+
+impl Instance {
+    fn format(&self, name: &str, depth: usize, output: &mut dyn io::Write) {
+        let indent = "  ".repeat(depth);
+        let _ = writeln!(output, "{}(INSTANCE {}", indent, escape(name));
+        if !self.nets.is_empty() {
+            let _ = writeln!(output, "{}  (NET", indent);
+            for (net, bit) in &self.nets {
+                let _ = writeln!(
+                    output,
+                    "{}    ({}\n{}      (T0 {}) (T1 {}) (TX {}) (TZ {})\n{}      (TC {})\n{}    )",
+                    indent, net, indent, bit.t0, bit.t1, bit.tx, bit.tz, indent, bit.tc, indent
+                );
+            }
+            let _ = writeln!(output, "{}  )", indent);
+        }
+        for (name, instance) in &self.instances {
+            instance.format(name, depth + 1, output)
+        }
+        let _ = writeln!(output, "{})", indent);
+    }
+}
+
+impl SwitchingActivity {
+    /// Compute the switching activity of the signals matching a glob pattern (or of all the
+    /// signals if no pattern is given) from `begin` included to `end` excluded. The whole dump is
+    /// considered if no time window is given, and the window is cut at the end of the dump.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::SwitchingActivity;
+    /// use dwfv::signaldb::{Scale, SignalDB, Timestamp};
+    /// let vcd = std::io::Cursor::new("$timescale 1ns $end
+    /// $scope module top $end
+    /// $var wire 1 0 clk $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// x0
+    /// #10
+    /// 00
+    /// #20
+    /// 10
+    /// #25
+    /// 00
+    /// #40
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let activity = SwitchingActivity::compute(&db, None, None, None).unwrap();
+    /// let clk = activity.get("top.clk", 0).unwrap();
+    /// assert_eq!((clk.t0, clk.t1, clk.tx, clk.tz, clk.tc), (25, 5, 10, 0, 2));
+    ///
+    /// let (begin, end) = (Timestamp::new(15, Scale::Nanosecond), Timestamp::new(1, Scale::Second));
+    /// let activity = SwitchingActivity::compute(&db, None, Some(begin), Some(end)).unwrap();
+    /// let clk = activity.get("top.clk", 0).unwrap();
+    /// assert_eq!((clk.t0, clk.t1, clk.tx, clk.tz, clk.tc), (20, 5, 0, 0, 2));
+    ///
+    /// assert!(SwitchingActivity::compute(&db, None, Some(end), Some(begin)).is_err());
+    /// ```
+    pub fn compute(
+        signaldb: &SignalDB,
+        pattern: Option<&str>,
+        begin: Option<Timestamp>,
+        end: Option<Timestamp>,
+    ) -> Result<SwitchingActivity, AnalysisError> {
+        let ids = select_signals(signaldb, pattern);
+        let timescale = signaldb.get_timescale();
+        let last = signaldb.get_last_timestamp();
+        let begin = begin.unwrap_or_else(Timestamp::origin);
+        let end = end.map_or(last, |end| end.min(last));
+        check_window(begin, end)?;
+        let mut top = Instance::default();
+
+        signaldb.with_signals(|signals| {
+            for id in &ids {
+                let signal = &signals[id];
+                let mut instance = &mut top;
+                for scope in signal.path.iter().skip(1) {
+                    instance = instance.instances.entry(scope.clone()).or_default()
+                }
+                let activity = BitActivity::compute(signal, begin, end, timescale);
+                if signal.width == 1 {
+                    instance.nets.push((escape(&signal.name), activity[0]))
+                } else {
+                    for (i, bit) in activity.into_iter().enumerate().rev() {
+                        let name = format!("{}[{}]", signal.name, i);
+                        instance.nets.push((escape(&name), bit))
+                    }
+                }
+            }
+        });
+
+        Ok(SwitchingActivity {
+            timescale,
+            duration: (end - begin) / timescale,
+            top,
+        })
+    }
+
+    /// Get the activity of a bit of a signal referenced by its hierarchical name.
+    pub fn get(&self, name: &str, bit: usize) -> Option<BitActivity> {
+        let mut path = name.split('.').collect::<Vec<_>>();
+        let signal = path.pop()?;
+        let mut instance = &self.top;
+        for scope in path {
+            instance = instance.instances.get(scope)?
+        }
+        let net = escape(&format!("{}[{}]", signal, bit));
+        let single = escape(signal);
+        instance
+            .nets
+            .iter()
+            .find(|(name, _)| *name == net || (bit == 0 && *name == single))
+            .map(|(_, activity)| *activity)
+    }
+
+    /// Format the switching activity as a SAIF file.
+    pub fn format_saif(&self, output: &mut dyn io::Write) {
+        let _ = writeln!(output, "(SAIFILE");
+        let _ = writeln!(output, "(SAIFVERSION \"2.0\")");
+        let _ = writeln!(output, "(DIRECTION \"backward\")");
+        let _ = writeln!(output, "(DESIGN )");
+        let _ = writeln!(output, "(PROGRAM_NAME \"{}\")", env!("CARGO_PKG_NAME"));
+        let _ = writeln!(output, "(VERSION \"{}\")", env!("CARGO_PKG_VERSION"));
+        let _ = writeln!(output, "(DIVIDER / )");
+        let _ = writeln!(
+            output,
+            "(TIMESCALE {} {})",
+            self.timescale.value, self.timescale.scale
+        );
+        let _ = writeln!(output, "(DURATION {})", self.duration);
+        for (name, instance) in &self.top.instances {
+            instance.format(name, 0, output)
+        }
+        let _ = writeln!(output, ")");
+    }
+}
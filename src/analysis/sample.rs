@@ -0,0 +1,222 @@
+// SPDX-License-Identifier: MIT
+use super::{AnalysisError, csv_field, json_string, resolve_signal, select_signals};
+use crate::signaldb::{BitValue, SignalDB, SignalValue, Timestamp};
+use std::error::Error;
+use std::io;
+
+/// Radix used to print the values of the signals
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Radix {
+    /// Binary, one digit per bit
+    Bin,
+    /// Unsigned decimal
+    Dec,
+    /// Hexadecimal
+    Hex,
+}
+
+impl Radix {
+    /// Parse a `Radix` from its name (`bin`, `dec` or `hex`).
+    pub fn parse(s: &str) -> Result<Radix, AnalysisError> {
+        match s.trim() {
+            "bin" => Ok(Radix::Bin),
+            "dec" => Ok(Radix::Dec),
+            "hex" => Ok(Radix::Hex),
+            _ => Err(AnalysisError::new(&format!("unknown radix '{}'", s))),
+        }
+    }
+
+    /// Format a value without any prefix. Symbols are printed as they are, and in decimal, values
+    /// containing unknown bits are printed as `x`, or as `u` if all of them are undefined.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::Radix;
+    /// use dwfv::signaldb::{BitValue, SignalValue};
+    /// let value = SignalValue::new(42);
+    /// assert_eq!(Radix::Bin.format(&value), "101010");
+    /// assert_eq!(Radix::Dec.format(&value), "42");
+    /// assert_eq!(Radix::Hex.format(&value), "2A");
+    /// assert_eq!(Radix::Dec.format(&SignalValue::new_default(8, BitValue::Invalid)), "x");
+    /// assert_eq!(Radix::Dec.format(&SignalValue::invalid()), "u");
+    /// ```
+    pub fn format(self, value: &SignalValue) -> String {
+        if let SignalValue::Symbol(symbol) = value {
+            return symbol.clone();
+        }
+        match self {
+            Radix::Bin => (0..value.width().max(1))
+                .rev()
+                .map(|i| value.get_bit(i).to_char())
+                .collect(),
+            Radix::Dec => match value.to_u64() {
+                Some(v) => v.to_string(),
+                None if (0..value.width().max(1))
+                    .all(|i| value.get_bit(i) == BitValue::Undefined) =>
+                {
+                    String::from("u")
+                }
+                None => String::from("x"),
+            },
+            Radix::Hex => value.to_string().trim_start_matches('h').to_string(),
+        }
+    }
+}
+
+/// Values of a set of signals sampled on the rising edges of a clock
+#[derive(Debug, Default)]
+pub struct CycleTable {
+    columns: Vec<String>,
+    rows: Vec<(Timestamp, Vec<SignalValue>)>,
+}
+
+impl CycleTable {
+    /// Sample the signals matching a glob pattern (or all the signals if no pattern is given) on
+    /// each rising edge of `clock` between `begin` and `end`. As seen by the logic clocked by
+    /// `clock`, the values are the ones held just before the edge.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{CycleTable, Radix};
+    /// use dwfv::signaldb::SignalDB;
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 clk $end
+    /// $var wire 4 1 count $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// b0 1
+    /// #10
+    /// 10
+    /// b1 1
+    /// #15
+    /// 00
+    /// #20
+    /// 10
+    /// b1010 1
+    /// #30
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let table = CycleTable::sample(&db, "clk", Some("count"), None, None).unwrap();
+    /// assert_eq!(table.rows().len(), 2);
+    ///
+    /// let mut buf = Vec::new();
+    /// table.format_csv(Radix::Dec, &mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "time,top.count[:4]\n10ps,0\n20ps,1\n"
+    /// );
+    ///
+    /// let mut buf = Vec::new();
+    /// table.format_markdown(Radix::Bin, &mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "| time | top.count[:4] |\n\
+    ///     | --- | --- |\n\
+    ///     | 10ps | 0000 |\n\
+    ///     | 20ps | 0001 |\n"
+    /// );
+    /// ```
+    pub fn sample(
+        signaldb: &SignalDB,
+        clock: &str,
+        pattern: Option<&str>,
+        begin: Option<Timestamp>,
+        end: Option<Timestamp>,
+    ) -> Result<CycleTable, Box<dyn Error>> {
+        let ids = select_signals(signaldb, pattern);
+        let begin = begin.unwrap_or_else(Timestamp::origin);
+        let end = end.unwrap_or_else(|| signaldb.get_time());
+        signaldb.with_signals(|signals| {
+            let clock = &signals[&resolve_signal(signals, clock)?];
+            let columns = ids
+                .iter()
+                .map(|id| signals[id].get_fullname())
+                .collect::<Vec<_>>();
+
+            let mut rows = Vec::new();
+            let mut edge = clock.get_next_rising_edge(begin - begin.derive(1));
+            while let Some(timestamp) = edge {
+                if timestamp > end {
+                    break;
+                }
+                let sample = timestamp - timestamp.derive(1);
+                let values = ids.iter().map(|id| signals[id].value_at(sample)).collect();
+                rows.push((timestamp, values));
+                edge = clock.get_next_rising_edge(timestamp)
+            }
+            Ok(CycleTable { columns, rows })
+        })
+    }
+
+    /// Get the full names of the sampled signals.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Get the timestamp of each sampled clock edge, along with the values of the signals.
+    pub fn rows(&self) -> &[(Timestamp, Vec<SignalValue>)] {
+        &self.rows
+    }
+
+    /// Format the table in CSV, with one column per signal.
+    pub fn format_csv(&self, radix: Radix, output: &mut dyn io::Write) {
+        let header = self
+            .columns
+            .iter()
+            .map(|c| csv_field(c))
+            .collect::<Vec<_>>();
+        let _ = writeln!(output, "time,{}", header.join(","));
+        for (timestamp, values) in &self.rows {
+            let values = values
+                .iter()
+                .map(|v| csv_field(&radix.format(v)))
+                .collect::<Vec<_>>();
+            let _ = writeln!(output, "{},{}", timestamp, values.join(","));
+        }
+    }
+
+    /// Format the table in JSON, as an array of objects mapping the names of the signals to their
+    /// values.
+    pub fn format_json(&self, radix: Radix, output: &mut dyn io::Write) {
+        let rows = self
+            .rows
+            .iter()
+            .map(|(timestamp, values)| {
+                let mut fields = vec![format!("\"time\": {}", json_string(&timestamp.to_string()))];
+                for (column, value) in self.columns.iter().zip(values) {
+                    fields.push(format!(
+                        "{}: {}",
+                        json_string(column),
+                        json_string(&radix.format(value))
+                    ))
+                }
+                format!("  {{{}}}", fields.join(", "))
+            })
+            .collect::<Vec<_>>();
+        if rows.is_empty() {
+            let _ = writeln!(output, "[]");
+        } else {
+            let _ = writeln!(output, "[\n{}\n]", rows.join(",\n"));
+        }
+    }
+
+    /// Format the table in Markdown.
+    pub fn format_markdown(&self, radix: Radix, output: &mut dyn io::Write) {
+        let escape = |s: &str| s.replace('|', "\\|");
+        let header = self.columns.iter().map(|c| escape(c)).collect::<Vec<_>>();
+        let _ = writeln!(output, "| time | {} |", header.join(" | "));
+        let _ = writeln!(output, "| --- |{}", " --- |".repeat(self.columns.len()));
+        for (timestamp, values) in &self.rows {
+            let values = values
+                .iter()
+                .map(|v| escape(&radix.format(v)))
+                .collect::<Vec<_>>();
+            let _ = writeln!(output, "| {} | {} |", timestamp, values.join(" | "));
+        }
+    }
+}
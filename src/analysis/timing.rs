@@ -0,0 +1,243 @@
+// SPDX-License-Identifier: MIT
+use super::{AnalysisError, parse_time, resolve_signal};
+use crate::signaldb::{Signal, SignalDB, SignalValue, TimeDescr, Timestamp};
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Setup and hold windows around the active edges of a clock, during which a data signal must
+/// remain stable
+#[derive(Debug, Clone)]
+pub struct SetupHoldCheck {
+    data: String,
+    setup: Timestamp,
+    hold: Timestamp,
+}
+
+/// Kind of timing violation
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TimingViolationKind {
+    /// The data changed within the setup window before the clock edge
+    Setup,
+    /// The data changed within the hold window after the clock edge
+    Hold,
+}
+
+/// Data change that does not fulfill a `SetupHoldCheck`
+#[derive(Debug, Clone)]
+pub struct TimingViolation {
+    /// Identifier of the data signal
+    pub data_id: String,
+    /// Kind of violation
+    pub kind: TimingViolationKind,
+    /// Timestamp of the data change
+    pub change: Timestamp,
+    /// Timestamp of the clock edge
+    pub edge: Timestamp,
+    /// Value of the data before the change
+    pub before: SignalValue,
+    /// Value of the data after the change
+    pub after: SignalValue,
+    /// Required window
+    pub window: Timestamp,
+}
+
+/// Result of setup/hold checks
+#[derive(Debug, Default)]
+pub struct TimingReport {
+    violations: Vec<TimingViolation>,
+}
+
+impl SetupHoldCheck {
+    /// Create a new `SetupHoldCheck` on a data signal referenced by its identifier, name or
+    /// hierarchical name.
+    pub fn new(data: &str, setup: Timestamp, hold: Timestamp) -> SetupHoldCheck {
+        SetupHoldCheck {
+            data: data.to_string(),
+            setup,
+            hold,
+        }
+    }
+
+    /// Parse a setup/hold check with the format `DATA:SETUP:HOLD`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::SetupHoldCheck;
+    /// use dwfv::signaldb::SignalDB;
+    /// let db = SignalDB::new();
+    ///
+    /// assert!(SetupHoldCheck::parse(&db, "data:10:5").is_ok());
+    /// assert!(SetupHoldCheck::parse(&db, "data:10").is_err());
+    /// ```
+    pub fn parse(signaldb: &SignalDB, spec: &str) -> Result<SetupHoldCheck, AnalysisError> {
+        let mut fields = spec.rsplitn(3, ':');
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some(hold), Some(setup), Some(data)) => Ok(SetupHoldCheck::new(
+                data.trim(),
+                parse_time(signaldb, setup)?,
+                parse_time(signaldb, hold)?,
+            )),
+            _ => Err(AnalysisError::new(&format!(
+                "expected DATA:SETUP:HOLD, got '{}'",
+                spec
+            ))),
+        }
+    }
+
+    fn run(&self, data: &Signal, clock: &Signal) -> Vec<TimingViolation> {
+        let mut violations = Vec::new();
+        let mut before = SignalValue::invalid();
+
+        for (i, (change, after)) in data.events().enumerate() {
+            // A change occurring exactly on the clock edge violates the setup window.
+            let next_edge = clock.get_next_rising_edge(change - change.derive(1));
+            let previous_edge = clock.get_previous_rising_edge(change);
+
+            let violation = match (next_edge, previous_edge) {
+                (Some(edge), _) if self.setup.value > 0 && edge - self.setup < change => {
+                    Some((TimingViolationKind::Setup, edge, self.setup))
+                }
+                (_, Some(edge)) if change < edge + self.hold => {
+                    Some((TimingViolationKind::Hold, edge, self.hold))
+                }
+                _ => None,
+            };
+
+            // The initial value of the data is not a change.
+            if let (Some((kind, edge, window)), false) = (violation, i == 0) {
+                violations.push(TimingViolation {
+                    data_id: data.id.clone(),
+                    kind,
+                    change,
+                    edge,
+                    before: before.clone(),
+                    after: after.clone(),
+                    window,
+                })
+            }
+            before = after.clone();
+        }
+        violations
+    }
+}
+
+impl TimingReport {
+    /// Check that the data signals do not change within the setup and hold windows around the
+    /// rising edges of a clock.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::{SetupHoldCheck, TimingReport};
+    /// use dwfv::signaldb::SignalDB;
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 clk $end
+    /// $var wire 8 1 data $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// bx 1
+    /// #10
+    /// 10
+    /// #12
+    /// b1 1
+    /// #15
+    /// 00
+    /// #18
+    /// b10 1
+    /// #20
+    /// 10
+    /// #25
+    /// 00
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let check = SetupHoldCheck::parse(&db, "data:3:3").unwrap();
+    /// let report = TimingReport::check(&db, &[check], "clk").unwrap();
+    ///
+    /// let mut buf = Vec::new();
+    /// report.format(&db, &mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "1 (top.data[:8])\n  \
+    ///     12ps: hold violation, hxx -> h01 2ps after clock edge at 10ps (hold: 3ps)\n  \
+    ///     18ps: setup violation, h01 -> h02 2ps before clock edge at 20ps (setup: 3ps)\n"
+    /// );
+    /// ```
+    pub fn check(
+        signaldb: &SignalDB,
+        checks: &[SetupHoldCheck],
+        clock: &str,
+    ) -> Result<TimingReport, Box<dyn Error>> {
+        signaldb.with_signals(|signals| {
+            let clock = &signals[&resolve_signal(signals, clock)?];
+            let mut violations = Vec::new();
+            for check in checks {
+                let data = &signals[&resolve_signal(signals, &check.data)?];
+                violations.append(&mut check.run(data, clock))
+            }
+            Ok(TimingReport { violations })
+        })
+    }
+
+    /// Get the violations found by the checks.
+    pub fn violations(&self) -> &[TimingViolation] {
+        &self.violations
+    }
+
+    /// Get the time periods between the data changes and the clock edges.
+    pub fn findings(&self) -> Vec<TimeDescr> {
+        self.violations
+            .iter()
+            .map(|v| {
+                if v.change < v.edge {
+                    TimeDescr::Period(v.change, v.edge)
+                } else if v.edge < v.change {
+                    TimeDescr::Period(v.edge, v.change)
+                } else {
+                    TimeDescr::Point(v.change)
+                }
+            })
+            .collect()
+    }
+
+    /// Format the violations, grouped by data signal.
+    pub fn format(&self, signaldb: &SignalDB, output: &mut dyn io::Write) {
+        let mut current = None;
+        for violation in &self.violations {
+            if current != Some(&violation.data_id) {
+                let name = signaldb
+                    .get_signal_fullname(&violation.data_id)
+                    .unwrap_or_default();
+                let _ = writeln!(output, "{} ({})", violation.data_id, name);
+                current = Some(&violation.data_id)
+            }
+            let _ = writeln!(output, "  {}", violation);
+        }
+    }
+}
+
+impl fmt::Display for TimingViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, margin, position) = match self.kind {
+            TimingViolationKind::Setup => ("setup", self.edge - self.change, "before"),
+            TimingViolationKind::Hold => ("hold", self.change - self.edge, "after"),
+        };
+        write!(
+            f,
+            "{}: {} violation, {} -> {} {} {} clock edge at {} ({}: {})",
+            self.change,
+            kind,
+            self.before,
+            self.after,
+            margin,
+            position,
+            self.edge,
+            kind,
+            self.window
+        )
+    }
+}
@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: MIT
+use super::select_signals;
+use crate::signaldb::{Signal, SignalDB, SignalValue, TimeDescr, Timestamp};
+use std::io;
+
+/// Period during which a signal holds an unknown value
+#[derive(Debug, Clone)]
+pub struct Episode {
+    /// Beginning of the period
+    pub begin: Timestamp,
+    /// End of the period, `None` if the signal is still unknown at the end of the dump
+    pub end: Option<Timestamp>,
+    /// Unknown value taken by the signal at the beginning of the period
+    pub value: SignalValue,
+}
+
+/// Unknown values held by a signal after its initialization
+#[derive(Debug, Clone)]
+pub struct UnknownTrace {
+    /// Identifier of the signal
+    pub signal_id: String,
+    /// Full name of the signal
+    pub fullname: String,
+    /// Periods during which the signal holds an unknown value
+    pub episodes: Vec<Episode>,
+}
+
+/// Signals becoming unknown (`x`, `z`, `u`, ...), sorted by time of first appearance
+#[derive(Debug)]
+pub struct UnknownReport {
+    traces: Vec<UnknownTrace>,
+    last: Timestamp,
+}
+
+impl UnknownTrace {
+    /// Get the earliest time the signal became unknown.
+    pub fn first(&self) -> Timestamp {
+        self.episodes[0].begin
+    }
+
+    fn compute(signal: &Signal, begin: Timestamp, end: Timestamp) -> UnknownTrace {
+        let mut episodes: Vec<Episode> = Vec::new();
+        // Unknown values reported before the signal is first known come from its initialization.
+        let mut initialized = false;
+
+        for (timestamp, value) in signal.events() {
+            if timestamp > end {
+                break;
+            }
+            if !value.is_invalid() {
+                initialized = true;
+                if let Some(last) = episodes.last_mut()
+                    && last.end.is_none()
+                {
+                    last.end = Some(timestamp)
+                }
+            } else if initialized && episodes.last().is_none_or(|e| e.end.is_some()) {
+                episodes.push(Episode {
+                    begin: timestamp,
+                    end: None,
+                    value: value.clone(),
+                })
+            }
+        }
+        episodes.retain(|e| e.end.is_none_or(|t| t > begin));
+
+        UnknownTrace {
+            signal_id: signal.id.clone(),
+            fullname: signal.get_fullname(),
+            episodes,
+        }
+    }
+}
+
+impl UnknownReport {
+    /// Trace the unknown values of the signals matching a glob pattern (or of all the signals if
+    /// no pattern is given) between `begin` and `end`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::UnknownReport;
+    /// use dwfv::signaldb::SignalDB;
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 out $end
+    /// $var wire 4 1 data $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// x0
+    /// bxxxx 1
+    /// #10
+    /// 00
+    /// b0000 1
+    /// #20
+    /// b00x0 1
+    /// #25
+    /// x0
+    /// #30
+    /// b0001 1
+    /// #40
+    /// b0z01 1
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let report = UnknownReport::trace(&db, None, None, None);
+    ///
+    /// let mut buf = Vec::new();
+    /// report.format(&mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "1 (top.data[:4]): first unknown at 20ps\n  \
+    ///     20ps-30ps: hx\n  \
+    ///     40ps-: hz\n\
+    ///     0 (top.out): first unknown at 25ps\n  \
+    ///     25ps-: hx\n"
+    /// );
+    /// ```
+    pub fn trace(
+        signaldb: &SignalDB,
+        pattern: Option<&str>,
+        begin: Option<Timestamp>,
+        end: Option<Timestamp>,
+    ) -> UnknownReport {
+        let ids = select_signals(signaldb, pattern);
+        let begin = begin.unwrap_or_else(Timestamp::origin);
+        let last = signaldb.get_time();
+        let mut traces = signaldb.with_signals(|signals| {
+            ids.iter()
+                .map(|id| UnknownTrace::compute(&signals[id], begin, end.unwrap_or(last)))
+                .filter(|trace| !trace.episodes.is_empty())
+                .collect::<Vec<_>>()
+        });
+        traces.sort_by_key(|trace| trace.first());
+        UnknownReport { traces, last }
+    }
+
+    /// Get the signals that became unknown, sorted by time of first appearance.
+    pub fn traces(&self) -> &[UnknownTrace] {
+        &self.traces
+    }
+
+    /// Get the periods during which the signals hold unknown values.
+    pub fn findings(&self) -> Vec<TimeDescr> {
+        let mut findings = Vec::new();
+        for trace in &self.traces {
+            for episode in &trace.episodes {
+                let end = episode.end.unwrap_or(self.last);
+                findings.push(if end > episode.begin {
+                    TimeDescr::Period(episode.begin, end)
+                } else {
+                    TimeDescr::Point(episode.begin)
+                })
+            }
+        }
+        findings
+    }
+
+    /// Format the unknown periods of each signal.
+    pub fn format(&self, output: &mut dyn io::Write) {
+        for trace in &self.traces {
+            let _ = writeln!(
+                output,
+                "{} ({}): first unknown at {}",
+                trace.signal_id,
+                trace.fullname,
+                trace.first()
+            );
+            for episode in &trace.episodes {
+                let end = episode.end.map(|t| t.to_string()).unwrap_or_default();
+                let _ = writeln!(output, "  {}-{}: {}", episode.begin, end, episode.value);
+            }
+        }
+    }
+}
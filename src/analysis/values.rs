@@ -0,0 +1,211 @@
+// SPDX-License-Identifier: MIT
+use super::select_signals;
+use crate::signaldb::{Signal, SignalDB, SignalValue, Timestamp};
+use std::io;
+
+/// Time spent by a signal at a given value
+#[derive(Debug, Clone)]
+pub struct ValueTime {
+    /// Value of the signal
+    pub value: SignalValue,
+    /// Number of times the signal took the value
+    pub count: usize,
+    /// Total time spent at the value
+    pub time: Timestamp,
+}
+
+/// Distribution of the values of a signal over a time window
+#[derive(Debug, Clone)]
+pub struct ValueStats {
+    /// Identifier of the signal
+    pub signal_id: String,
+    /// Full name of the signal
+    pub fullname: String,
+    /// Length of the time window
+    pub duration: Timestamp,
+    /// Number of changes within the time window
+    pub changes: usize,
+    /// Known values, sorted by decreasing time
+    pub values: Vec<ValueTime>,
+    /// Number of times the signal became unknown (containing `x`, `z`, ...)
+    pub unknown_count: usize,
+    /// Total time spent at unknown values
+    pub unknown_time: Timestamp,
+}
+
+/// Distribution of the values of several signals
+#[derive(Debug, Default)]
+pub struct ValueReport {
+    stats: Vec<ValueStats>,
+}
+
+fn percent(time: Timestamp, duration: Timestamp) -> f64 {
+    if duration.value == 0 {
+        0.0
+    } else {
+        let (time, duration) = (time + duration.derive(0), duration + time.derive(0));
+        time.value as f64 * 100.0 / duration.value as f64
+    }
+}
+
+impl ValueStats {
+    fn compute(signal: &Signal, begin: Timestamp, end: Timestamp) -> ValueStats {
+        let (initial, changes, _) = signal.events_between(begin, end);
+        let mut stats = ValueStats {
+            signal_id: signal.id.clone(),
+            fullname: signal.get_fullname(),
+            duration: end - begin,
+            changes,
+            values: Vec::new(),
+            unknown_count: 0,
+            unknown_time: Timestamp::origin(),
+        };
+
+        let mut current = signal.value_at(begin);
+        if current != initial {
+            // The signal changed exactly at the beginning of the window.
+            stats.changes -= 1
+        }
+        let mut since = begin;
+        stats.enter(&current);
+        for (timestamp, value) in signal.events() {
+            if timestamp <= begin {
+                continue;
+            } else if timestamp >= end {
+                break;
+            }
+            stats.leave(&current, timestamp - since);
+            stats.enter(value);
+            current = value.clone();
+            since = timestamp
+        }
+        stats.leave(&current, end - since);
+
+        stats.values.sort_by_key(|v| std::cmp::Reverse(v.time));
+        stats
+    }
+
+    fn value(&mut self, value: &SignalValue) -> &mut ValueTime {
+        match self.values.iter().position(|v| v.value == *value) {
+            Some(index) => &mut self.values[index],
+            None => {
+                self.values.push(ValueTime {
+                    value: value.clone(),
+                    count: 0,
+                    time: Timestamp::origin(),
+                });
+                self.values.last_mut().unwrap()
+            }
+        }
+    }
+
+    fn enter(&mut self, value: &SignalValue) {
+        if value.is_invalid() {
+            self.unknown_count += 1
+        } else {
+            self.value(value).count += 1
+        }
+    }
+
+    fn leave(&mut self, value: &SignalValue, time: Timestamp) {
+        if value.is_invalid() {
+            self.unknown_time += time
+        } else {
+            self.value(value).time += time
+        }
+    }
+}
+
+impl ValueReport {
+    /// Compute the distribution of the values of the signals matching a glob pattern (or of all
+    /// the signals if no pattern is given) from `begin` included to `end` excluded. The whole
+    /// dump is considered if no time window is given.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::analysis::ValueReport;
+    /// use dwfv::signaldb::SignalDB;
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 2 0 level $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// bxx 0
+    /// #10
+    /// b01 0
+    /// #20
+    /// b10 0
+    /// #50
+    /// b01 0
+    /// #100
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let report = ValueReport::compute(&db, None, None, None);
+    ///
+    /// let mut buf = Vec::new();
+    /// report.format(&mut buf);
+    /// assert_eq!(
+    ///     String::from_utf8(buf).unwrap(),
+    ///     "0 (top.level[:2]) - changes: 3\n  \
+    ///     h1: 2 times, 60ps (60.0%)\n  \
+    ///     h2: 1 time, 30ps (30.0%)\n  \
+    ///     x/z: 1 time, 10ps (10.0%)\n"
+    /// );
+    /// ```
+    pub fn compute(
+        signaldb: &SignalDB,
+        pattern: Option<&str>,
+        begin: Option<Timestamp>,
+        end: Option<Timestamp>,
+    ) -> ValueReport {
+        let ids = select_signals(signaldb, pattern);
+        let begin = begin.unwrap_or_else(Timestamp::origin);
+        let end = end.unwrap_or_else(|| signaldb.get_time());
+        signaldb.with_signals(|signals| ValueReport {
+            stats: ids
+                .iter()
+                .map(|id| ValueStats::compute(&signals[id], begin, end))
+                .collect(),
+        })
+    }
+
+    /// Get the distribution of the values of each signal.
+    pub fn stats(&self) -> &[ValueStats] {
+        &self.stats
+    }
+
+    /// Format the distribution of the values of each signal.
+    pub fn format(&self, output: &mut dyn io::Write) {
+        let times = |count| if count == 1 { "time" } else { "times" };
+        for stats in &self.stats {
+            let _ = writeln!(
+                output,
+                "{} ({}) - changes: {}",
+                stats.signal_id, stats.fullname, stats.changes
+            );
+            for value in &stats.values {
+                let _ = writeln!(
+                    output,
+                    "  {}: {} {}, {} ({:.1}%)",
+                    value.value,
+                    value.count,
+                    times(value.count),
+                    value.time,
+                    percent(value.time, stats.duration)
+                );
+            }
+            if stats.unknown_count > 0 {
+                let _ = writeln!(
+                    output,
+                    "  x/z: {} {}, {} ({:.1}%)",
+                    stats.unknown_count,
+                    times(stats.unknown_count),
+                    stats.unknown_time,
+                    percent(stats.unknown_time, stats.duration)
+                );
+            }
+        }
+    }
+}
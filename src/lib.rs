@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: MIT
 
-mod search;
+pub mod analysis;
+pub mod search;
 pub mod signaldb;
 #[cfg(feature = "cli")]
 pub mod tui;
@@ -1,13 +1,21 @@
 #![cfg(feature = "cli")]
 
 // SPDX-License-Identifier: MIT
+use dwfv::analysis::{
+    self, AnalysisError, Assertion, AssertionKind, AssertionReport, BusPort, BusTrace, ChangeLog,
+    CoverageReport, CycleTable, FsmReport, InstructionTrace, LatencyReport, Memory, Pairing,
+    PulseCheck, PulseReport, Radix, RegisterMap, SetupHoldCheck, StateNames, SwitchingActivity,
+    TimingReport, UnknownReport, ValueReport, WritePort, Xlen,
+};
+use dwfv::search::{FindingsStats, ParseError};
 use dwfv::signaldb::{AsyncSignalDB, SignalDB};
 use dwfv::tui::Tui;
 use gumdrop::Options;
 use std::env;
 use std::error::Error;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{self, BufReader};
+use std::path::Path;
 use std::process;
 
 /// A simple digital waveform viewer with vi-like key bindings
@@ -33,32 +41,552 @@ struct Args {
     #[options(meta = "EXPR")]
     when: Option<String>,
 
-    /// Displays states of the signals at a given timestamp
+    /// Prints the number of findings of --when instead of the findings
+    #[options(no_short)]
+    count: bool,
+
+    /// Prints the number of findings of --when, the time they cover and the lengths of the periods
+    #[options(no_short)]
+    summary: bool,
+
+    /// Keeps only the first finding of --when (or the first --limit ones)
+    #[options(no_short)]
+    first: bool,
+
+    /// Keeps only the last finding of --when (or the last --limit ones)
+    #[options(no_short)]
+    last: bool,
+
+    /// Keeps at most N findings of --when (the last ones with --last)
+    #[options(no_short, meta = "N")]
+    limit: Option<usize>,
+
+    /// Fails if the expression is ever true, reporting its first findings (repeatable)
+    #[options(no_short, meta = "EXPR")]
+    assert_never: Vec<String>,
+
+    /// Fails if the expression is not true during the whole dump (repeatable)
+    #[options(no_short, meta = "EXPR")]
+    assert_always: Vec<String>,
+
+    /// Checks the assertions of a file, one `never EXPR` or `always EXPR` per line
+    #[options(no_short, meta = "FILE")]
+    checks: Option<String>,
+
+    /// Displays states of the signals at a given timestamp (e.g. 1337 or 10ns)
     #[options(meta = "TIMESTAMP")]
-    at: Option<i64>,
+    at: Option<String>,
+
+    /// Shows the time spent by the signals at each of their values
+    #[options(no_short)]
+    value_stats: bool,
+
+    /// Reports when the signals become unknown (x, z, ...) after their initialization
+    #[options(no_short)]
+    trace_unknowns: bool,
+
+    /// Reports pulses shorter than a threshold and simultaneous transitions
+    #[options(no_short, meta = "THRESHOLD")]
+    glitch: Option<String>,
+
+    /// Reports pulses shorter than a width (e.g. reset=1:10cycles)
+    #[options(no_short, meta = "SIGNAL[=VALUE]:WIDTH")]
+    min_pulse: Vec<String>,
+
+    /// Reports pulses longer than a width (e.g. busy=1:100)
+    #[options(no_short, meta = "SIGNAL[=VALUE]:WIDTH")]
+    max_pulse: Vec<String>,
+
+    /// Reports changes of a signal within the setup and hold windows of the clock edges
+    #[options(no_short, meta = "DATA:SETUP:HOLD")]
+    setup_hold: Vec<String>,
+
+    /// Reports the bits of the signals that did not rise and fall (text, json or lcov)
+    #[options(no_short, meta = "FORMAT")]
+    toggle_coverage: Option<String>,
+
+    /// Exports the switching activity of the signals in SAIF
+    #[options(no_short)]
+    saif: bool,
+
+    /// Measures the time between the findings of two expressions
+    #[options(no_short, meta = "\"START => END\"")]
+    latency: Option<String>,
+
+    /// Pairs the latency findings with the value of an identifier signal
+    #[options(no_short, meta = "SIGNAL[,SIGNAL]")]
+    latency_id: Option<String>,
+
+    /// Reports the states and the transitions of a state register
+    #[options(no_short, meta = "SIGNAL")]
+    fsm: Option<String>,
+
+    /// Names of the states (e.g. 0=IDLE,1=BUSY) or file containing one VALUE=NAME per line
+    #[options(no_short, meta = "NAMES")]
+    states: Option<String>,
+
+    /// Exports the transition graph of the state register in the DOT language
+    #[options(no_short)]
+    dot: bool,
+
+    /// Reconstructs the content of a memory from its write port (dumped at --to if given)
+    #[options(no_short, meta = "CLK,WE,ADDR,DATA")]
+    memory: Option<String>,
+
+    /// Shows the content and the last write of a single address of the memory
+    #[options(no_short, meta = "ADDR")]
+    address: Option<String>,
+
+    /// Prints the instructions retired on the --clock edges on which VALID is high
+    #[options(no_short, meta = "VALID,PC,INSN")]
+    riscv_trace: Option<String>,
+
+    /// Disassembles the instructions as RV64 instead of RV32
+    #[options(no_short)]
+    rv64: bool,
+
+    /// Adds the registers x0-x31 reconstructed from a writeback port to --when, --at and the TUI
+    #[options(no_short, meta = "CLK,WE,RD,DATA")]
+    shadow_regs: Option<String>,
+
+    /// Adds the accesses of a bus, decoded with --regmap, to --when, --at and the TUI
+    #[options(no_short, meta = "CLK,VALID,WRITE,ADDR,DATA")]
+    decode_bus: Option<String>,
+
+    /// Register map (CMSIS-SVD or CSV file) used to decode the bus accesses
+    #[options(no_short, meta = "FILE")]
+    regmap: Option<String>,
+
+    /// Lists the decoded bus accesses to the registers or fields matching a glob pattern
+    #[options(no_short, meta = "GLOB")]
+    register: Option<String>,
+
+    /// Prints the values of the signals on each rising edge of a clock
+    #[options(no_short, meta = "CLK")]
+    sample_on: Option<String>,
+
+    /// Output format of the sampled values (csv, json or markdown)
+    #[options(no_short, meta = "FORMAT")]
+    format: Option<String>,
+
+    /// Exports the value changes of the signals (csv, json or jsonl)
+    #[options(no_short, meta = "FORMAT")]
+    export_changes: Option<String>,
+
+    /// Radix of the printed values (bin, dec or hex)
+    #[options(no_short, meta = "RADIX")]
+    radix: Option<String>,
+
+    /// Restricts the analysis to the signals matching comma-separated glob patterns
+    #[options(no_short, meta = "GLOBS")]
+    signals: Option<String>,
+
+    /// Starts the analysis at a given timestamp
+    #[options(no_short, meta = "TIMESTAMP")]
+    from: Option<String>,
+
+    /// Ends the analysis at a given timestamp
+    #[options(no_short, meta = "TIMESTAMP")]
+    to: Option<String>,
+
+    /// Clock used to count cycles, to check setup and hold times and to sample instructions
+    #[options(no_short, meta = "SIGNAL")]
+    clock: Option<String>,
 
     /// Value Change Dump (VCD) file to parse
     #[options(free, required)]
     file: String,
 }
 
+fn decode_bus(db: &SignalDB, spec: &str, regmap: Option<&str>) -> Result<BusTrace, Box<dyn Error>> {
+    let map = match regmap {
+        Some(path) => RegisterMap::parse(&fs::read_to_string(path)?)?,
+        None => RegisterMap::default(),
+    };
+    BusTrace::decode(db, &BusPort::parse(spec)?, map)
+}
+
+fn parse_radix(radix: Option<&str>) -> Result<Radix, AnalysisError> {
+    radix.map_or(Ok(Radix::Hex), Radix::parse)
+}
+
+/// Check that at most one analysis is requested, and that the options given apply to it, the TUI
+/// being opened if there is none.
+fn check_options(args: &Args) -> Result<(), String> {
+    let assertions = if !args.assert_never.is_empty() {
+        "--assert-never"
+    } else if !args.assert_always.is_empty() {
+        "--assert-always"
+    } else {
+        "--checks"
+    };
+    let pulses = if args.min_pulse.is_empty() {
+        "--max-pulse"
+    } else {
+        "--min-pulse"
+    };
+    let modes = [
+        ("--at", args.at.is_some()),
+        ("--when", args.when.is_some()),
+        (
+            assertions,
+            !args.assert_never.is_empty()
+                || !args.assert_always.is_empty()
+                || args.checks.is_some(),
+        ),
+        ("--glitch", args.glitch.is_some()),
+        (
+            pulses,
+            !args.min_pulse.is_empty() || !args.max_pulse.is_empty(),
+        ),
+        ("--setup-hold", !args.setup_hold.is_empty()),
+        ("--toggle-coverage", args.toggle_coverage.is_some()),
+        ("--saif", args.saif),
+        ("--latency", args.latency.is_some()),
+        ("--fsm", args.fsm.is_some()),
+        ("--memory", args.memory.is_some()),
+        ("--riscv-trace", args.riscv_trace.is_some()),
+        ("--register", args.register.is_some()),
+        ("--sample-on", args.sample_on.is_some()),
+        ("--export-changes", args.export_changes.is_some()),
+        ("--value-stats", args.value_stats),
+        ("--trace-unknowns", args.trace_unknowns),
+        ("--stats", args.stats),
+    ];
+    let mut selected = modes
+        .iter()
+        .filter(|(_, given)| *given)
+        .map(|(mode, _)| *mode);
+    let mode = selected.next();
+    if let (Some(mode), Some(other)) = (mode, selected.next()) {
+        return Err(format!("{} and {} cannot be combined", mode, other));
+    }
+    if args.first && args.last {
+        return Err("--first and --last cannot be combined".to_string());
+    }
+    if args.regmap.is_some() && args.decode_bus.is_none() {
+        return Err("--regmap requires a --decode-bus".to_string());
+    }
+
+    // Analyses to which each option applies, the TUI being represented by `None`
+    let windowed = vec![
+        Some("--toggle-coverage"),
+        Some("--saif"),
+        Some("--fsm"),
+        Some("--riscv-trace"),
+        Some("--sample-on"),
+        Some("--export-changes"),
+        Some("--value-stats"),
+        Some("--trace-unknowns"),
+    ];
+    let virtual_signals = vec![
+        Some("--at"),
+        Some("--when"),
+        Some("--assert-never"),
+        Some("--assert-always"),
+        Some("--checks"),
+        Some("--sample-on"),
+        Some("--export-changes"),
+        None,
+    ];
+    let options = [
+        ("--count", args.count, vec![Some("--when")]),
+        ("--summary", args.summary, vec![Some("--when")]),
+        ("--first", args.first, vec![Some("--when")]),
+        ("--last", args.last, vec![Some("--when")]),
+        ("--limit", args.limit.is_some(), vec![Some("--when")]),
+        (
+            "--latency-id",
+            args.latency_id.is_some(),
+            vec![Some("--latency")],
+        ),
+        ("--states", args.states.is_some(), vec![Some("--fsm")]),
+        ("--dot", args.dot, vec![Some("--fsm")]),
+        ("--address", args.address.is_some(), vec![Some("--memory")]),
+        ("--rv64", args.rv64, vec![Some("--riscv-trace")]),
+        ("--format", args.format.is_some(), vec![Some("--sample-on")]),
+        (
+            "--radix",
+            args.radix.is_some(),
+            vec![Some("--sample-on"), Some("--export-changes")],
+        ),
+        (
+            "--signals",
+            args.signals.is_some(),
+            vec![
+                Some("--glitch"),
+                Some("--toggle-coverage"),
+                Some("--saif"),
+                Some("--sample-on"),
+                Some("--export-changes"),
+                Some("--value-stats"),
+                Some("--trace-unknowns"),
+            ],
+        ),
+        ("--from", args.from.is_some(), windowed.clone()),
+        (
+            "--to",
+            args.to.is_some(),
+            [windowed, vec![Some("--memory")]].concat(),
+        ),
+        (
+            "--clock",
+            args.clock.is_some(),
+            vec![
+                Some("--min-pulse"),
+                Some("--max-pulse"),
+                Some("--setup-hold"),
+                Some("--riscv-trace"),
+            ],
+        ),
+        ("--layout", args.layout.is_some(), vec![None]),
+        (
+            "--shadow-regs",
+            args.shadow_regs.is_some(),
+            virtual_signals.clone(),
+        ),
+        (
+            "--decode-bus",
+            args.decode_bus.is_some(),
+            [virtual_signals, vec![Some("--register")]].concat(),
+        ),
+    ];
+    for (option, given, modes) in options {
+        if !given || modes.contains(&mode) {
+            continue;
+        }
+        return Err(match mode {
+            Some(mode) => format!("{} cannot be combined with {}", option, mode),
+            None => {
+                let modes = modes.into_iter().flatten().collect::<Vec<_>>();
+                match modes.split_last() {
+                    Some((last, modes)) if !modes.is_empty() => {
+                        format!("{} requires a {} or {}", option, modes.join(", "), last)
+                    }
+                    _ => format!("{} requires a {}", option, modes.join(", ")),
+                }
+            }
+        });
+    }
+    Ok(())
+}
+
 /// Available subcommands
 fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    check_options(&args)?;
+    let assertions =
+        !args.assert_never.is_empty() || !args.assert_always.is_empty() || args.checks.is_some();
+
     let file = File::open(args.file)?;
     let buf_reader = BufReader::new(file);
 
+    // Signals computed from the dump, which can be used as if they had been dumped
+    let add_virtual_signals = |db: &SignalDB| -> Result<(), Box<dyn Error>> {
+        if let Some(spec) = &args.shadow_regs {
+            analysis::shadow_registers(db, &WritePort::parse(spec)?, "regfile")?;
+        }
+        if let Some(spec) = &args.decode_bus {
+            decode_bus(db, spec, args.regmap.as_deref())?.declare_signal(db, "bus")?;
+        }
+        Ok(())
+    };
+
     if let Some(timestamp) = args.at {
-        let db = SignalDB::from_vcd_with_limit(buf_reader, Some(timestamp))?;
-        db.format_values_at(&mut io::stdout(), timestamp)
+        let db = match timestamp.trim().parse::<i64>() {
+            Ok(ticks) => SignalDB::from_vcd_with_limit(buf_reader, Some(ticks))?,
+            Err(_) => SignalDB::from_vcd_until(buf_reader, timestamp.parse()?)?,
+        };
+        add_virtual_signals(&db)?;
+        let timestamp = analysis::parse_time(&db, &timestamp)?;
+        db.format_values_at_time(&mut io::stdout(), timestamp)
     } else if let Some(expr) = args.when {
-        let mut db = SignalDB::from_vcd(buf_reader)?;
-        db.search_all(&mut io::stdout(), &expr)?
+        let db = SignalDB::from_vcd(buf_reader)?;
+        add_virtual_signals(&db)?;
+        let limit = match args.limit {
+            None if args.first || args.last => Some(1),
+            limit => limit,
+        };
+        let mut findings = db.find_all(&expr, if args.last { None } else { limit })?;
+        if args.last
+            && let Some(limit) = limit
+        {
+            findings.drain(..findings.len().saturating_sub(limit));
+        }
+
+        if args.summary {
+            FindingsStats::new(&findings).format(&mut io::stdout())
+        } else if args.count {
+            println!("{}", findings.len())
+        } else {
+            for finding in findings {
+                println!("{}", finding)
+            }
+        }
+    } else if assertions {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        add_virtual_signals(&db)?;
+        let mut assertions = Vec::new();
+        if let Some(path) = &args.checks {
+            assertions.append(&mut Assertion::parse_checks(&fs::read_to_string(path)?)?)
+        }
+        for expr in &args.assert_never {
+            assertions.push(Assertion::new(AssertionKind::Never, expr)?)
+        }
+        for expr in &args.assert_always {
+            assertions.push(Assertion::new(AssertionKind::Always, expr)?)
+        }
+        let report = AssertionReport::check(&db, &assertions)?;
+        report.format(&db, &mut io::stdout());
+        if !report.is_success() {
+            return Err(format!(
+                "{} of {} assertions failed",
+                report.failures(),
+                assertions.len()
+            )
+            .into());
+        }
+    } else if let Some(threshold) = args.glitch {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        let threshold = analysis::parse_time(&db, &threshold)?;
+        PulseReport::glitches(&db, args.signals.as_deref(), threshold)
+            .format(&db, &mut io::stdout())
+    } else if !args.min_pulse.is_empty() || !args.max_pulse.is_empty() {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        let mut checks = Vec::new();
+        for spec in &args.min_pulse {
+            checks.push(PulseCheck::parse_min(&db, spec)?)
+        }
+        for spec in &args.max_pulse {
+            checks.push(PulseCheck::parse_max(&db, spec)?)
+        }
+        PulseReport::check(&db, &checks, args.clock.as_deref())?.format(&db, &mut io::stdout())
+    } else if !args.setup_hold.is_empty() {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        let clock = args.clock.ok_or("--setup-hold requires a --clock")?;
+        let mut checks = Vec::new();
+        for spec in &args.setup_hold {
+            checks.push(SetupHoldCheck::parse(&db, spec)?)
+        }
+        TimingReport::check(&db, &checks, &clock)?.format(&db, &mut io::stdout())
+    } else if let Some(format) = args.toggle_coverage {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        let (begin, end) = analysis::parse_window(&db, args.from.as_deref(), args.to.as_deref())?;
+        let report = CoverageReport::toggle(&db, args.signals.as_deref(), begin, end);
+        match format.as_str() {
+            "text" => report.format(&mut io::stdout()),
+            "json" => report.format_json(&mut io::stdout()),
+            "lcov" => report.format_lcov(&mut io::stdout()),
+            _ => return Err(format!("unknown coverage format '{}'", format).into()),
+        }
+    } else if args.saif {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        let (begin, end) = analysis::parse_window(&db, args.from.as_deref(), args.to.as_deref())?;
+        SwitchingActivity::compute(&db, args.signals.as_deref(), begin, end)?
+            .format_saif(&mut io::stdout())
+    } else if let Some(spec) = args.latency {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        let (start, end) = analysis::parse_latency(&spec)?;
+        let pairing = match args.latency_id {
+            Some(id) => Pairing::parse_id(&id),
+            None => Pairing::Fifo,
+        };
+        LatencyReport::measure(&db, start, end, &pairing)?.format(&mut io::stdout())
+    } else if let Some(signal) = args.fsm {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        let (begin, end) = analysis::parse_window(&db, args.from.as_deref(), args.to.as_deref())?;
+        let names = match args.states {
+            Some(names) if Path::new(&names).is_file() => {
+                StateNames::parse(&fs::read_to_string(names)?)?
+            }
+            Some(names) => StateNames::parse(&names)?,
+            None => StateNames::default(),
+        };
+        let report = FsmReport::extract(&db, &signal, names, begin, end)?;
+        if args.dot {
+            report.format_dot(&mut io::stdout())
+        } else {
+            report.format(&db, &mut io::stdout())
+        }
+    } else if let Some(port) = args.memory {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        let (_, end) = analysis::parse_window(&db, None, args.to.as_deref())?;
+        let timestamp = end.unwrap_or_else(|| db.get_time());
+        let memory = Memory::reconstruct(&db, &WritePort::parse(&port)?)?;
+        match args.address {
+            Some(address) => {
+                let address = analysis::parse_address(&address)?;
+                match memory.last_write(address, timestamp) {
+                    Some(write) => println!(
+                        "{:x}: {} (written at {})",
+                        address, write.data, write.timestamp
+                    ),
+                    None => println!("{:x}: never written", address),
+                }
+            }
+            None => memory.format_dump(timestamp, &mut io::stdout()),
+        }
+    } else if let Some(spec) = args.riscv_trace {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        let clock = args.clock.ok_or("--riscv-trace requires a --clock")?;
+        let (begin, end) = analysis::parse_window(&db, args.from.as_deref(), args.to.as_deref())?;
+        let [valid, pc, insn] = spec.split(',').map(str::trim).collect::<Vec<_>>()[..] else {
+            return Err(format!("expected VALID,PC,INSN, got '{}'", spec).into());
+        };
+        let xlen = if args.rv64 { Xlen::Rv64 } else { Xlen::Rv32 };
+        InstructionTrace::capture(&db, &clock, valid, pc, insn, xlen, begin, end)?
+            .format(&mut io::stdout())
+    } else if let Some(pattern) = args.register {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        let spec = args
+            .decode_bus
+            .as_deref()
+            .ok_or("--register requires a --decode-bus")?;
+        decode_bus(&db, spec, args.regmap.as_deref())?.format(Some(&pattern), &mut io::stdout())
+    } else if let Some(clock) = args.sample_on {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        add_virtual_signals(&db)?;
+        let (begin, end) = analysis::parse_window(&db, args.from.as_deref(), args.to.as_deref())?;
+        let radix = parse_radix(args.radix.as_deref())?;
+        let table = CycleTable::sample(&db, &clock, args.signals.as_deref(), begin, end)?;
+        match args.format.as_deref().unwrap_or("csv") {
+            "csv" => table.format_csv(radix, &mut io::stdout()),
+            "json" => table.format_json(radix, &mut io::stdout()),
+            "markdown" => table.format_markdown(radix, &mut io::stdout()),
+            format => return Err(format!("unknown table format '{}'", format).into()),
+        }
+    } else if let Some(format) = args.export_changes {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        add_virtual_signals(&db)?;
+        let (begin, end) = analysis::parse_window(&db, args.from.as_deref(), args.to.as_deref())?;
+        let radix = parse_radix(args.radix.as_deref())?;
+        let log = ChangeLog::collect(&db, args.signals.as_deref(), begin, end);
+        match format.as_str() {
+            "csv" => log.format_csv(radix, &mut io::stdout()),
+            "json" => log.format_json(radix, &mut io::stdout()),
+            "jsonl" => log.format_jsonl(radix, &mut io::stdout()),
+            _ => return Err(format!("unknown export format '{}'", format).into()),
+        }
+    } else if args.value_stats {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        let (begin, end) = analysis::parse_window(&db, args.from.as_deref(), args.to.as_deref())?;
+        ValueReport::compute(&db, args.signals.as_deref(), begin, end).format(&mut io::stdout())
+    } else if args.trace_unknowns {
+        let db = SignalDB::from_vcd(buf_reader)?;
+        let (begin, end) = analysis::parse_window(&db, args.from.as_deref(), args.to.as_deref())?;
+        UnknownReport::trace(&db, args.signals.as_deref(), begin, end).format(&mut io::stdout())
     } else if args.stats {
         let db = SignalDB::from_vcd(buf_reader)?;
         db.format_stats(&mut io::stdout())
     } else {
         let mut adb = AsyncSignalDB::new();
-        adb.parse_vcd(buf_reader);
+        if args.shadow_regs.is_some() || args.decode_bus.is_some() {
+            // The virtual signals can only be computed once the whole file is parsed.
+            adb.sync_db.parse_vcd(buf_reader)?;
+            add_virtual_signals(&adb.sync_db)?;
+            adb.sync_db.mark_as_initialized()
+        } else {
+            adb.parse_vcd(buf_reader);
+        }
 
         adb.sync_db.wait_until_initialized()?;
         let mut tui = Tui::new(adb)?;
@@ -94,6 +622,9 @@ fn main() {
 
     if let Err(e) = run(opts) {
         eprintln!("Error: {}", e);
+        if let Some(err) = e.downcast_ref::<ParseError>() {
+            err.format_caret(&mut io::stderr())
+        }
         process::exit(1);
     }
 }
@@ -1,7 +1,15 @@
 // SPDX-License-Identifier: MIT
+//! Search expressions, used to find the points and periods of time matching a condition.
 mod expr;
-mod parser;
+pub(crate) mod parser;
+mod query;
+mod stats;
 pub(crate) mod types;
 
+pub use self::expr::{
+    Comparison, ExprAst, LengthAst, ParseError, Pattern, SampledFunction, TimeAst, ValueAst,
+};
+pub use self::query::Query;
+pub use self::stats::FindingsStats;
 pub use self::types::FindingsSummary;
 pub(crate) use self::types::Search;
@@ -1,43 +1,393 @@
 // SPDX-License-Identifier: MIT
 use super::parser;
-use crate::signaldb::SignalValue;
+use crate::signaldb::{BitValue, SignalValue, Timestamp};
+use regex::Regex;
 use std::error::Error;
+use std::fmt;
 use std::io;
+use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq)]
+/// Error reported when a search expression is invalid
+///
+/// # Example
+///
+/// ```
+/// use dwfv::search::ExprAst;
+/// let err = "$a = 1 and $b =".parse::<ExprAst>().unwrap_err();
+/// assert_eq!(err.column(), 16);
+/// assert_eq!(err.token(), "");
+/// assert_eq!(err.expected(), "value after `=`");
+/// assert_eq!(
+///     err.to_string(),
+///     "Syntax Error at column 16: unexpected end of expression, expected value after `=`"
+/// );
+///
+/// let err = "$a = 1 foo".parse::<ExprAst>().unwrap_err();
+/// let mut buf = Vec::new();
+/// err.format_caret(&mut buf);
+/// assert_eq!(String::from_utf8(buf).unwrap(), "$a = 1 foo\n       ^^^\n");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    expr: String,
+    column: usize,
+    token: String,
+    expected: String,
+}
+
+impl ParseError {
+    fn new(expr: &str, rest: &str, expected: &str) -> ParseError {
+        let rest = rest.trim_start();
+        let offset = expr.len() - rest.len();
+        let token = match rest.chars().next() {
+            Some(c) if c == '(' || c == ')' => c.to_string(),
+            _ => rest
+                .split(char::is_whitespace)
+                .next()
+                .unwrap_or("")
+                .to_string(),
+        };
+        ParseError {
+            expr: expr.to_string(),
+            column: expr[..offset].chars().count() + 1,
+            token,
+            expected: expected.to_string(),
+        }
+    }
+
+    /// Get the invalid expression.
+    pub fn expr(&self) -> &str {
+        &self.expr
+    }
+
+    /// Get the column (starting from 1) at which the expression stops being valid.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Get the offending token, which is empty if the expression ends too early.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Get a description of what was expected instead of the offending token.
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+
+    /// Format the expression with a caret under the offending token.
+    pub fn format_caret(&self, output: &mut dyn io::Write) {
+        let _ = writeln!(output, "{}", self.expr);
+        let _ = writeln!(
+            output,
+            "{}{}",
+            " ".repeat(self.column - 1),
+            "^".repeat(self.token.chars().count().max(1))
+        );
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Syntax Error at column {}: ", self.column)?;
+        if self.token.is_empty() {
+            write!(f, "unexpected end of expression")?
+        } else {
+            write!(f, "unexpected `{}`", self.token)?
+        }
+        write!(f, ", expected {}", self.expected)
+    }
+}
+
+/// Value compared to a signal in a search expression
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ValueAst {
+    /// Literal value (e.g. `42`, `h2a` or `b101010`)
     Literal(SignalValue),
+    /// Value of another signal, referenced by its identifier
     Id(String),
+    /// Literal value with don't-care bits (e.g. `b1??0` or `h1?`), along with the mask of its
+    /// don't-care bits
+    Wildcard(SignalValue, SignalValue),
+}
+
+/// Regular expression matched against the symbolic value of a signal (`/ERR.*/`)
+///
+/// # Example
+///
+/// ```
+/// use dwfv::search::Pattern;
+/// let pattern = Pattern::new("ERR.*").unwrap();
+/// assert_eq!(pattern.as_str(), "ERR.*");
+/// assert!(pattern.is_match("BUS_ERROR"));
+/// assert!(!pattern.is_match("IDLE"));
+/// assert!(Pattern::new("ERR(").is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    regex: Regex,
+}
+
+impl Pattern {
+    /// Compile a regular expression.
+    pub fn new(pattern: &str) -> Result<Pattern, regex::Error> {
+        Ok(Pattern {
+            regex: Regex::new(pattern)?,
+        })
+    }
+
+    /// Get the source of the regular expression.
+    pub fn as_str(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    /// Check whether the regular expression matches a part of a string.
+    pub fn is_match(&self, haystack: &str) -> bool {
+        self.regex.is_match(haystack)
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Pattern {}
+
+/// Sampled-value function, comparing the current sample of a signal to the previous one
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SampledFunction {
+    /// The least significant bit changes from 0 to 1 (`$rose`)
+    Rose,
+    /// The least significant bit changes from 1 to 0 (`$fell`)
+    Fell,
+    /// The value does not change (`$stable`)
+    Stable,
+    /// The value changes (`$changed`)
+    Changed,
+}
+
+/// Comparison operator
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Comparison {
+    /// `<`
+    Less,
+    /// `<=`
+    LessOrEqual,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterOrEqual,
+    /// `=`
+    Equal,
+}
+
+impl Comparison {
+    /// Compare two values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::search::Comparison;
+    /// assert!(Comparison::GreaterOrEqual.compare(2, 2));
+    /// assert!(!Comparison::Less.compare(3, 2));
+    /// ```
+    pub fn compare<T: PartialOrd>(self, left: T, right: T) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Equal => left == right,
+        }
+    }
+}
+
+/// Time in a search expression
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TimeAst {
+    /// Time expressed in the time scale of the dump (`42`)
+    Ticks(i64),
+    /// Time expressed with a unit (`10ns`, `1.5us`)
+    Timestamp(Timestamp),
+}
+
+impl TimeAst {
+    /// Get the time as a timestamp, ticks being expressed in the scale of `reference`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::search::TimeAst;
+    /// use dwfv::signaldb::{Scale, Timestamp};
+    /// let reference = Timestamp::new(1337, Scale::Picosecond);
+    /// assert_eq!(
+    ///     TimeAst::Ticks(42).to_timestamp(reference),
+    ///     Timestamp::new(42, Scale::Picosecond)
+    /// );
+    /// assert_eq!(
+    ///     TimeAst::Timestamp(Timestamp::new(42, Scale::Nanosecond)).to_timestamp(reference),
+    ///     Timestamp::new(42000, Scale::Picosecond)
+    /// );
+    /// ```
+    pub fn to_timestamp(self, reference: Timestamp) -> Timestamp {
+        match self {
+            TimeAst::Ticks(value) => reference.derive(value),
+            TimeAst::Timestamp(timestamp) => timestamp,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Length of a finding in a search expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LengthAst {
+    /// Length expressed as a time
+    Time(TimeAst),
+    /// Length expressed as a number of rising edges of a clock, referenced by its identifier
+    Cycles(i64, String),
+}
+
+/// Abstract syntax tree of a search expression
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ExprAst {
+    /// The signal holds a value (`$id = value`)
     Equal(String, ValueAst),
+    /// The signal changes to a value (`$id <- value`)
     Transition(String, ValueAst),
+    /// The signal changes (`$id`)
     AnyTransition(String),
+    /// One of the bits of the signal is neither 0 nor 1 (`$id is unknown`)
+    Unknown(String),
+    /// One of the bits of the signal has a value (`$id has z`)
+    Has(String, BitValue),
+    /// The symbolic value of the signal matches a regular expression (`$id matches /ERR.*/`)
+    Matches(String, Pattern),
+    /// The symbolic value of the signal contains a string (`$id contains "timeout"`)
+    Contains(String, String),
+    /// Negation of an expression
     Not(Box<ExprAst>),
+    /// Both expressions are true
     And(Box<ExprAst>, Box<ExprAst>),
+    /// Either expression is true
     Or(Box<ExprAst>, Box<ExprAst>),
-    After(i64),
-    Before(i64),
+    /// The time is after a timestamp (`after 42`, `after 10ns`)
+    After(TimeAst),
+    /// The time is before a timestamp (`before 42`, `before 10ns`)
+    Before(TimeAst),
+    /// Findings of an expression whose length satisfies a constraint (`expr for > 10`). The
+    /// constraint applies to the whole expression: it is only valid at the top level, and the
+    /// parser never nests it under another operator.
+    For(Box<ExprAst>, Comparison, LengthAst),
+    /// A sampled-value function is true for a signal, sampled on the rising edges of a clock if
+    /// any (`$rose($id)`, `$stable($id, $clk)`)
+    Sampled(SampledFunction, String, Option<String>),
+    /// A signal held a value (or a different value, if the flag is false) a number of samples
+    /// ago, sampled on the rising edges of a clock if any (`$past($id, 2) = value`). Both are
+    /// false if there is no such sample.
+    Past(String, usize, Option<String>, bool, ValueAst),
 }
 
 impl ExprAst {
-    pub(crate) fn from_str(expr: &str) -> Result<ExprAst, Box<dyn Error>> {
-        let (_, ast) = parser::expr(expr).map_err(|err| {
-            io::Error::new(
-                io::ErrorKind::InvalidInput,
-                format!("Syntax Error: {:?}", err),
-            )
-        })?;
-        Ok(ast)
+    /// Get the identifiers of the signals referenced by the expression, without duplicates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::search::ExprAst;
+    /// let ast: ExprAst = "$a <- $b or $b = 1 and $c".parse().unwrap();
+    /// assert_eq!(ast.signal_ids(), vec!["a", "b", "c"]);
+    ///
+    /// let ast: ExprAst = "$clk or $a for < 2 cycles of $clk".parse().unwrap();
+    /// assert_eq!(ast.signal_ids(), vec!["clk", "a"]);
+    /// ```
+    pub fn signal_ids(&self) -> Vec<&str> {
+        let mut ids = Vec::new();
+        self.collect_signal_ids(&mut ids);
+        ids
+    }
+
+    fn collect_signal_ids<'a>(&'a self, ids: &mut Vec<&'a str>) {
+        let mut push = |id: &'a str| {
+            if !ids.contains(&id) {
+                ids.push(id)
+            }
+        };
+        match self {
+            ExprAst::Equal(id, value) | ExprAst::Transition(id, value) => {
+                push(id);
+                if let ValueAst::Id(other) = value {
+                    push(other)
+                }
+            }
+            ExprAst::AnyTransition(id)
+            | ExprAst::Unknown(id)
+            | ExprAst::Has(id, _)
+            | ExprAst::Matches(id, _)
+            | ExprAst::Contains(id, _) => push(id),
+            ExprAst::Not(e) => e.collect_signal_ids(ids),
+            ExprAst::And(le, re) | ExprAst::Or(le, re) => {
+                le.collect_signal_ids(ids);
+                re.collect_signal_ids(ids)
+            }
+            ExprAst::For(e, _, length) => {
+                e.collect_signal_ids(ids);
+                if let LengthAst::Cycles(_, clock) = length
+                    && !ids.contains(&clock.as_str())
+                {
+                    ids.push(clock)
+                }
+            }
+            ExprAst::Sampled(_, id, clock) => {
+                push(id);
+                if let Some(clock) = clock {
+                    push(clock)
+                }
+            }
+            ExprAst::Past(id, _, clock, _, value) => {
+                push(id);
+                if let ValueAst::Id(other) = value {
+                    push(other)
+                }
+                if let Some(clock) = clock {
+                    push(clock)
+                }
+            }
+            ExprAst::After(_) | ExprAst::Before(_) => {}
+        }
+    }
+}
+
+impl FromStr for ExprAst {
+    type Err = ParseError;
+
+    fn from_str(expr: &str) -> Result<ExprAst, ParseError> {
+        match parser::query(expr) {
+            Ok((rest, ast)) if rest.trim().is_empty() => Ok(ast),
+            Ok((rest, _)) => Err(ParseError::new(
+                expr,
+                rest,
+                "`and`, `nand`, `or`, `for` or the end of the expression",
+            )),
+            Err(nom::Err::Error(err)) | Err(nom::Err::Failure(err)) => Err(ParseError::new(
+                expr,
+                err.input,
+                err.expected.as_deref().unwrap_or("expression"),
+            )),
+            Err(nom::Err::Incomplete(_)) => Err(ParseError::new(expr, "", "expression")),
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::signaldb::BitValue;
 
     #[test]
     fn expr() {
@@ -58,6 +408,74 @@ mod test {
         assert!(ExprAst::from_str("$a <- $b").is_ok());
     }
 
+    #[test]
+    fn errors() {
+        let check = |expr: &str, column, token: &str, expected: &str| {
+            let err = ExprAst::from_str(expr).unwrap_err();
+            assert_eq!(
+                (err.column(), err.token(), err.expected()),
+                (column, token, expected)
+            );
+        };
+        check("bz = bz", 1, "bz", "expression");
+        check("$a = ", 6, "", "value after `=`");
+        check("$a becomes foo", 12, "foo", "value after `becomes`");
+        check("$a = (1", 8, "", "`)`");
+        check("($a = 1", 8, "", "`)`");
+        check("$a = 1 and", 11, "", "expression after `and`");
+        check("$a = 1 or 2", 11, "2", "expression after `or`");
+        check("after foo", 7, "foo", "timestamp after `after`");
+        check(
+            "$a = 1 )",
+            8,
+            ")",
+            "`and`, `nand`, `or`, `for` or the end of the expression",
+        );
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            ExprAst::from_str("$a and $b or $c").unwrap(),
+            ExprAst::Or(
+                Box::new(ExprAst::And(
+                    Box::new(ExprAst::AnyTransition("a".to_string())),
+                    Box::new(ExprAst::AnyTransition("b".to_string())),
+                )),
+                Box::new(ExprAst::AnyTransition("c".to_string())),
+            )
+        );
+    }
+
+    #[test]
+    fn test_for() {
+        assert_eq!(
+            ExprAst::from_str("$a = 1 for >= 10").unwrap(),
+            ExprAst::For(
+                Box::new(ExprAst::Equal(
+                    "a".to_string(),
+                    ValueAst::Literal(SignalValue::new(1))
+                )),
+                Comparison::GreaterOrEqual,
+                LengthAst::Time(TimeAst::Ticks(10))
+            )
+        );
+        assert_eq!(
+            ExprAst::from_str("$a or $b for < 3 cycles of $clk").unwrap(),
+            ExprAst::For(
+                Box::new(ExprAst::Or(
+                    Box::new(ExprAst::AnyTransition("a".to_string())),
+                    Box::new(ExprAst::AnyTransition("b".to_string())),
+                )),
+                Comparison::Less,
+                LengthAst::Cycles(3, "clk".to_string())
+            )
+        );
+        assert!(ExprAst::from_str("($a for > 2) and $b").is_err());
+        assert!(ExprAst::from_str("$a for > 2 or $b").is_err());
+        assert!(ExprAst::from_str("$a for > 2 for < 5").is_err());
+    }
+
     #[test]
     fn test_eq() {
         assert_eq!(
@@ -151,7 +569,7 @@ mod test {
                     "a".to_string(),
                     ValueAst::Literal(SignalValue::new(0)),
                 )),
-                Box::new(ExprAst::After(42)),
+                Box::new(ExprAst::After(TimeAst::Ticks(42))),
             )
         );
 
@@ -162,7 +580,7 @@ mod test {
                     "a".to_string(),
                     ValueAst::Literal(SignalValue::new(0)),
                 )),
-                Box::new(ExprAst::Before(42)),
+                Box::new(ExprAst::Before(TimeAst::Ticks(42))),
             )
         );
     }
@@ -3,8 +3,10 @@
 /// Grammar of the search expressions:
 ///
 /// ```ebnf
+/// query = expr, [ "for", comparison, length ];
+///
 /// expr =
-///     expr, "or", expr_tier
+///     expr_tier, "or", expr
 ///     | expr_tier
 ///     ;
 ///
@@ -15,14 +17,24 @@
 ///
 /// expr_term =
 ///     left_value, equal, right_value
+///     | left_value, "is", ( "known" | "unknown" )
+///     | left_value, "has", bit_value
+///     | left_value, "matches", regex
+///     | left_value, "contains", string
 ///     | left_value, not_equal, right_value
 ///     | left_value, transition, right_value
-///     | "after" dec_value
-///     | "before" dec_value
+///     | "after" time_value
+///     | "before" time_value
+///     | sampled_function, "(", argument, [ ",", argument ], ")"
+///     | "$past", "(", argument, [ ",", dec_value ], [ ",", argument ], ")",
+///       ( equal | not_equal ), right_value
 ///     | left_value
 ///     | "(" expr ")"
 ///     ;
 ///
+/// comparison = "<" | "<=" | ">" | ">=" | "=";
+/// length = time_value | dec_value, ( "cycle" | "cycles" ), "of", id;
+///
 /// equal = "is" | "equals" | "=";
 /// not_equal = "is not", "!=";
 /// transition = "becomes", "<-";
@@ -40,23 +52,54 @@
 ///     | hex_value
 ///     ;
 ///
+/// sampled_function = "$rose" | "$fell" | "$stable" | "$changed";
+///
 /// id = \$[[:graph:]]+;
-/// bin_value = b[01uzw-]+;
-/// hex_value = h[0-9A-Fa-f]+;
+/// argument = \$[^,)[:space:]]+;
+/// bin_value = b[01uzw?-]+;
+/// hex_value = h[0-9A-Fa-f?]+;
+/// bit_value = [01xzuw-];
 /// dec_value = [0-9]+;
+/// regex = "/", ( [^/\\] | "\\", . )*, "/";
+/// string = '"', ( [^"\\] | "\\", . )*, '"';
+/// time_value = [0-9]+, [ ".", [0-9]+ ], [ "s" | "ms" | "us" | "ns" | "ps" | "fs" ];
 /// ```
-use super::expr::{ExprAst, ValueAst};
-use crate::signaldb::SignalValue;
+use super::expr::{Comparison, ExprAst, LengthAst, Pattern, SampledFunction, TimeAst, ValueAst};
+use crate::signaldb::{BitValue, SignalValue};
 use nom::{
     IResult, Parser,
     branch::alt,
     bytes::complete::{tag, take, take_while, take_while_m_n, take_while1},
-    combinator::{opt, recognize},
-    error::Error,
+    combinator::{all_consuming, opt, recognize},
+    error::{ErrorKind, ParseError},
     sequence::{delimited, pair, preceded, separated_pair},
 };
 use std::str::FromStr;
 
+/// Error reported by the parsers, along with what was expected at this position
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct SyntaxError<'a> {
+    pub(crate) input: &'a str,
+    pub(crate) code: ErrorKind,
+    pub(crate) expected: Option<String>,
+}
+
+impl<'a> ParseError<&'a str> for SyntaxError<'a> {
+    fn from_error_kind(input: &'a str, code: ErrorKind) -> Self {
+        SyntaxError {
+            input,
+            code,
+            expected: None,
+        }
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+type PResult<'a, O> = IResult<&'a str, O, SyntaxError<'a>>;
+
 // Check functions
 
 fn is_digit_start(input: char) -> bool {
@@ -68,11 +111,15 @@ fn is_digit(input: char) -> bool {
 }
 
 fn is_binary_digit(input: char) -> bool {
-    matches!(input, '0' | '1' | 'u' | 'z' | 'w' | '-')
+    matches!(input, '0' | '1' | 'u' | 'z' | 'w' | '-' | '?')
 }
 
 fn is_hex_digit(input: char) -> bool {
-    input.is_ascii_hexdigit()
+    input.is_ascii_hexdigit() || input == '?'
+}
+
+fn is_bit(input: char) -> bool {
+    matches!(input, '0' | '1' | 'x' | 'z' | 'u' | 'w' | '-')
 }
 
 fn is_identifier(input: char) -> bool {
@@ -82,42 +129,133 @@ fn is_identifier(input: char) -> bool {
 // Combinators
 
 /// Call a parser with optional whitespace on either side.
-fn token<'a, O, F>(parser: F) -> impl Parser<&'a str, Output = O, Error = Error<&'a str>>
+fn token<'a, O, F>(parser: F) -> impl Parser<&'a str, Output = O, Error = SyntaxError<'a>>
 where
-    F: Fn(&'a str) -> IResult<&'a str, O>,
+    F: Fn(&'a str) -> PResult<'a, O>,
 {
     delimited(opt(whitespace), parser, opt(whitespace))
 }
 
+/// Call a parser which must succeed, as the input cannot be recognized otherwise. The error
+/// reports what was expected.
+fn expect<'a, O, F>(mut parser: F, expected: &str) -> impl FnMut(&'a str) -> PResult<'a, O>
+where
+    F: Parser<&'a str, Output = O, Error = SyntaxError<'a>>,
+{
+    let expected = expected.to_string();
+    move |input| {
+        parser.parse(input).map_err(|err| match err {
+            nom::Err::Error(mut err) => {
+                err.expected = Some(expected.clone());
+                nom::Err::Failure(err)
+            }
+            err => err,
+        })
+    }
+}
+
 // Parsers
 
 /// Recognize whitespace.
-fn whitespace(input: &str) -> IResult<&str, &str> {
+fn whitespace(input: &str) -> PResult<'_, &str> {
     take_while1(char::is_whitespace)(input)
 }
 
+/// Recognize an expression, optionally followed by a constraint on the length of its findings.
+pub(crate) fn query(input: &str) -> PResult<'_, ExprAst> {
+    pair(expr, opt(constraint))
+        .parse(input)
+        .map(|(rest, (expr, constraint))| {
+            let expr = match constraint {
+                Some((comparison, length)) => ExprAst::For(Box::new(expr), comparison, length),
+                None => expr,
+            };
+            (rest, expr)
+        })
+}
+
+/// Recognize a constraint on the length of the findings.
+fn constraint(input: &str) -> PResult<'_, (Comparison, LengthAst)> {
+    preceded(
+        token(tag("for")),
+        pair(
+            expect(token(comparison_operator), "comparison after `for`"),
+            expect(length, "duration after the comparison"),
+        ),
+    )
+    .parse(input)
+}
+
+/// Recognize a comparison operator.
+fn comparison_operator(input: &str) -> PResult<'_, Comparison> {
+    alt((tag("<="), tag(">="), tag("<"), tag(">"), tag("=")))
+        .parse(input)
+        .map(|(rest, op)| {
+            let comparison = match op {
+                "<=" => Comparison::LessOrEqual,
+                ">=" => Comparison::GreaterOrEqual,
+                "<" => Comparison::Less,
+                ">" => Comparison::Greater,
+                _ => Comparison::Equal,
+            };
+            (rest, comparison)
+        })
+}
+
+/// Recognize a length, expressed as a time or as a number of cycles of a clock.
+fn length(input: &str) -> PResult<'_, LengthAst> {
+    let (rest, value) = token(time).parse(input)?;
+    let (rest, cycles) = opt(alt((tag("cycles"), tag("cycle")))).parse(rest)?;
+    let value = match (value, cycles) {
+        (value, None) => return Ok((rest, LengthAst::Time(value))),
+        (TimeAst::Ticks(value), Some(_)) => value,
+        (TimeAst::Timestamp(_), Some(_)) => {
+            return Err(nom::Err::Error(SyntaxError::from_error_kind(
+                input,
+                ErrorKind::Digit,
+            )));
+        }
+    };
+    let (rest, _) = expect(token(tag("of")), "`of` after `cycles`").parse(rest)?;
+    let (rest, clock) = expect(token(identifier), "clock after `of`").parse(rest)?;
+    let clock = match clock {
+        ValueAst::Id(id) => id,
+        _ => unreachable!(),
+    };
+    Ok((rest, LengthAst::Cycles(value, clock)))
+}
+
 /// Recognize an expression.
-pub(crate) fn expr(input: &str) -> IResult<&str, ExprAst> {
+pub(crate) fn expr(input: &str) -> PResult<'_, ExprAst> {
     alt((or, tier)).parse(input)
 }
 
 /// Recognize a tiered expression.
-fn tier(input: &str) -> IResult<&str, ExprAst> {
+fn tier(input: &str) -> PResult<'_, ExprAst> {
     alt((and, nand, term)).parse(input)
 }
 
 /// Recognize an expression term.
-fn term(input: &str) -> IResult<&str, ExprAst> {
-    alt((parens, equal, not_equal, transition, before, after, any)).parse(input)
+fn term(input: &str) -> PResult<'_, ExprAst> {
+    alt((
+        parens, sampled, past, known, has, matches, contains, not_equal, equal, transition, before,
+        after, any,
+    ))
+    .parse(input)
 }
 
 /// Recognize an expression in parentheses.
-fn parens(input: &str) -> IResult<&str, ExprAst> {
-    delimited(tag("("), expr, tag(")")).parse(input)
+fn parens(input: &str) -> PResult<'_, ExprAst> {
+    delimited(
+        tag("("),
+        expect(expr, "expression after `(`"),
+        expect(tag(")"), "`)`"),
+    )
+    .parse(input)
 }
 
 /// Recognize a number.
-fn number(input: &str) -> IResult<&str, ValueAst> {
+fn number(input: &str) -> PResult<'_, ValueAst> {
     recognize(alt((
         pair(tag("b"), take_while1(is_binary_digit)),
         pair(tag("h"), take_while1(is_hex_digit)),
@@ -126,99 +264,292 @@ fn number(input: &str) -> IResult<&str, ValueAst> {
     )))
     .parse(input)
     .map(|(rest, value)| {
-        let value = match &value[..1] {
-            "b" => SignalValue::from_str(&value[1..]).unwrap(),
-            "h" => SignalValue::from_hex(&value[1..]),
-            _ => SignalValue::new(value.parse().unwrap()),
+        let (radix, digits) = match &value[..1] {
+            "b" | "h" => value.split_at(1),
+            _ => ("", value),
+        };
+        // Don't-care digits are zeroed in the value and set in the mask of don't-care bits.
+        let value = digits.replace('?', "0");
+        let ignored: String = digits
+            .chars()
+            .map(|c| match (c, radix) {
+                ('?', "b") => '1',
+                ('?', _) => 'f',
+                _ => '0',
+            })
+            .collect();
+        let (value, ignored) = match radix {
+            "b" => (
+                SignalValue::from_str(&value).unwrap(),
+                SignalValue::from_str(&ignored).unwrap(),
+            ),
+            "h" => (
+                SignalValue::from_hex(&value),
+                SignalValue::from_hex(&ignored),
+            ),
+            _ => (
+                SignalValue::new(value.parse().unwrap()),
+                SignalValue::new(0),
+            ),
         };
 
-        (rest, ValueAst::Literal(value))
+        if digits.contains('?') {
+            (rest, ValueAst::Wildcard(value, ignored))
+        } else {
+            (rest, ValueAst::Literal(value))
+        }
     })
 }
 
-/// Recognize a decimal number.
-fn decimal(input: &str) -> IResult<&str, i64> {
-    recognize(alt((
-        pair(tag("0"), take(0_usize)),
-        pair(take_while_m_n(1, 1, is_digit_start), take_while(is_digit)),
-    )))
-    .parse(input)
-    .map(|(rest, value)| (rest, value.parse().unwrap()))
+/// Parse a literal value, as written on the right-hand side of a condition.
+pub(crate) fn literal(input: &str) -> Option<SignalValue> {
+    match all_consuming(number).parse(input.trim()) {
+        Ok((_, ValueAst::Literal(value))) => Some(value),
+        _ => None,
+    }
+}
+
+/// Recognize a time, either a number of ticks or a number followed by a unit.
+fn time(input: &str) -> PResult<'_, TimeAst> {
+    let (rest, literal) = recognize((
+        take_while1(is_digit),
+        opt(pair(tag("."), take_while1(is_digit))),
+        opt(alt((
+            tag("ms"),
+            tag("us"),
+            tag("ns"),
+            tag("ps"),
+            tag("fs"),
+            tag("s"),
+        ))),
+    ))
+    .parse(input)?;
+    if let Ok(ticks) = literal.parse() {
+        Ok((rest, TimeAst::Ticks(ticks)))
+    } else if let Ok(timestamp) = literal.parse() {
+        Ok((rest, TimeAst::Timestamp(timestamp)))
+    } else {
+        Err(nom::Err::Error(SyntaxError::from_error_kind(
+            input,
+            ErrorKind::Digit,
+        )))
+    }
+}
+
+/// Recognize the value of a bit.
+fn bit(input: &str) -> PResult<'_, BitValue> {
+    take_while_m_n(1, 1, is_bit)
+        .parse(input)
+        .map(|(rest, bit)| (rest, BitValue::from_char(bit.chars().next().unwrap())))
 }
 
 /// Recognize an identifier.
-fn identifier(input: &str) -> IResult<&str, ValueAst> {
+fn identifier(input: &str) -> PResult<'_, ValueAst> {
     preceded(tag("$"), take_while1(is_identifier))
         .parse(input)
         .map(|(rest, id)| (rest, ValueAst::Id(id.to_string())))
 }
 
 /// Recognize a value in parentheses.
-fn value_parens(input: &str) -> IResult<&str, ValueAst> {
-    delimited(tag("("), value, tag(")")).parse(input)
+fn value_parens(input: &str) -> PResult<'_, ValueAst> {
+    delimited(
+        tag("("),
+        expect(value, "value after `(`"),
+        expect(tag(")"), "`)`"),
+    )
+    .parse(input)
 }
 
 /// Recognize a value.
-fn value(input: &str) -> IResult<&str, ValueAst> {
+fn value(input: &str) -> PResult<'_, ValueAst> {
     alt((number, identifier, value_parens)).parse(input)
 }
 
+/// Recognize one of the operators followed by the value compared to.
+fn compared_value<'a, 'b>(
+    input: &'a str,
+    operators: &[&'b str],
+) -> PResult<'a, (&'b str, ValueAst)> {
+    let Some(operator) = operators.iter().find(|op| input.starts_with(**op)) else {
+        return Err(nom::Err::Error(SyntaxError::from_error_kind(
+            input,
+            ErrorKind::Tag,
+        )));
+    };
+    let (rest, right) = expect(token(value), &format!("value after `{}`", operator))
+        .parse(&input[operator.len()..])?;
+
+    Ok((rest, (operator, right)))
+}
+
+/// Recognize a signal compared to a value with one of the operators.
+fn comparison<'a>(input: &'a str, operators: &[&str]) -> PResult<'a, (String, ValueAst)> {
+    let (rest, left) = token(identifier).parse(input)?;
+    let (rest, (_, right)) = compared_value(rest, operators)?;
+    let left = match left {
+        ValueAst::Id(id) => id,
+        _ => unreachable!(),
+    };
+
+    Ok((rest, (left, right)))
+}
+
 /// Recognize an equivalence condition.
-fn equal(input: &str) -> IResult<&str, ExprAst> {
-    separated_pair(
+fn equal(input: &str) -> PResult<'_, ExprAst> {
+    comparison(input, &["=", "is", "equals"])
+        .map(|(rest, (left, right))| (rest, ExprAst::Equal(left, right)))
+}
+
+/// Recognize a non-equivalence condition.
+fn not_equal(input: &str) -> PResult<'_, ExprAst> {
+    comparison(input, &["!=", "is not"])
+        .map(|(rest, (left, right))| (rest, ExprAst::Not(Box::new(ExprAst::Equal(left, right)))))
+}
+
+/// Recognize whether the value of a signal is fully known.
+fn known(input: &str) -> PResult<'_, ExprAst> {
+    pair(
         token(identifier),
-        alt((tag("="), tag("is"), tag("equals"))),
-        token(value),
+        preceded(
+            pair(tag("is"), whitespace),
+            alt((tag("known"), tag("unknown"))),
+        ),
     )
     .parse(input)
-    .map(|(rest, (left, right))| {
-        let left = match left {
+    .map(|(rest, (id, predicate))| {
+        let id = match id {
             ValueAst::Id(id) => id,
             _ => unreachable!(),
         };
+        let unknown = ExprAst::Unknown(id);
+        let expr = match predicate {
+            "known" => ExprAst::Not(Box::new(unknown)),
+            _ => unknown,
+        };
 
-        (rest, ExprAst::Equal(left, right))
+        (rest, expr)
     })
 }
 
-/// Recognize a non-equivalence condition.
-fn not_equal(input: &str) -> IResult<&str, ExprAst> {
-    separated_pair(
+/// Recognize whether one of the bits of a signal has a value.
+fn has(input: &str) -> PResult<'_, ExprAst> {
+    pair(
         token(identifier),
-        alt((tag("!="), tag("is not"))),
-        token(value),
+        preceded(tag("has"), expect(token(bit), "bit value after `has`")),
     )
     .parse(input)
-    .map(|(rest, (left, right))| {
-        let left = match left {
+    .map(|(rest, (id, bit))| {
+        let id = match id {
             ValueAst::Id(id) => id,
             _ => unreachable!(),
         };
 
-        (rest, ExprAst::Not(Box::new(ExprAst::Equal(left, right))))
+        (rest, ExprAst::Has(id, bit))
     })
 }
 
-/// Recognize a transition.
-fn transition(input: &str) -> IResult<&str, ExprAst> {
-    separated_pair(
+/// Recognize a literal delimited by `delimiter`, in which `\` escapes the next character.
+/// Escaped delimiters are unescaped, and the other escape sequences are kept if `keep_escapes`.
+fn quoted(input: &str, delimiter: char, keep_escapes: bool) -> PResult<'_, String> {
+    let Some(mut chars) = input
+        .strip_prefix(delimiter)
+        .map(|rest| rest.char_indices())
+    else {
+        return Err(nom::Err::Error(SyntaxError::from_error_kind(
+            input,
+            ErrorKind::Char,
+        )));
+    };
+    let mut literal = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, c)) if c == delimiter => literal.push(c),
+                Some((_, c)) if keep_escapes => {
+                    literal.push('\\');
+                    literal.push(c)
+                }
+                Some((_, c)) => literal.push(c),
+                None => break,
+            },
+            c if c == delimiter => return Ok((&input[i + 2 * delimiter.len_utf8()..], literal)),
+            c => literal.push(c),
+        }
+    }
+
+    Err(nom::Err::Failure(SyntaxError {
+        input: &input[input.len()..],
+        code: ErrorKind::Char,
+        expected: Some(format!("`{}`", delimiter)),
+    }))
+}
+
+/// Recognize a regular expression delimited by slashes.
+fn regex(input: &str) -> PResult<'_, Pattern> {
+    let (rest, pattern) = quoted(input, '/', true)?;
+    match Pattern::new(&pattern) {
+        Ok(pattern) => Ok((rest, pattern)),
+        Err(_) => Err(nom::Err::Failure(SyntaxError {
+            input,
+            code: ErrorKind::Verify,
+            expected: Some("valid regular expression".to_string()),
+        })),
+    }
+}
+
+/// Recognize a string delimited by double quotes.
+fn string(input: &str) -> PResult<'_, String> {
+    quoted(input, '"', false)
+}
+
+/// Recognize whether the symbolic value of a signal matches a regular expression.
+fn matches(input: &str) -> PResult<'_, ExprAst> {
+    pair(
         token(identifier),
-        alt((tag("<-"), tag("becomes"))),
-        token(value),
+        preceded(
+            tag("matches"),
+            expect(token(regex), "regular expression after `matches`"),
+        ),
     )
     .parse(input)
-    .map(|(rest, (left, right))| {
-        let left = match left {
+    .map(|(rest, (id, pattern))| {
+        let id = match id {
+            ValueAst::Id(id) => id,
+            _ => unreachable!(),
+        };
+
+        (rest, ExprAst::Matches(id, pattern))
+    })
+}
+
+/// Recognize whether the symbolic value of a signal contains a string.
+fn contains(input: &str) -> PResult<'_, ExprAst> {
+    pair(
+        token(identifier),
+        preceded(
+            tag("contains"),
+            expect(token(string), "string after `contains`"),
+        ),
+    )
+    .parse(input)
+    .map(|(rest, (id, string))| {
+        let id = match id {
             ValueAst::Id(id) => id,
             _ => unreachable!(),
         };
 
-        (rest, ExprAst::Transition(left, right))
+        (rest, ExprAst::Contains(id, string))
     })
 }
 
+/// Recognize a transition.
+fn transition(input: &str) -> PResult<'_, ExprAst> {
+    comparison(input, &["<-", "becomes"])
+        .map(|(rest, (left, right))| (rest, ExprAst::Transition(left, right)))
+}
+
 /// Recognize any transition.
-fn any(input: &str) -> IResult<&str, ExprAst> {
+fn any(input: &str) -> PResult<'_, ExprAst> {
     token(identifier).parse(input).map(|(rest, value)| {
         let value = match value {
             ValueAst::Id(id) => id,
@@ -229,43 +560,141 @@ fn any(input: &str) -> IResult<&str, ExprAst> {
     })
 }
 
+/// Recognize a signal passed to a function, whose identifier cannot contain `,` or `)`.
+fn argument(input: &str) -> PResult<'_, String> {
+    preceded(
+        tag("$"),
+        take_while1(|c| is_identifier(c) && c != ',' && c != ')'),
+    )
+    .parse(input)
+    .map(|(rest, id)| (rest, id.to_string()))
+}
+
+/// Recognize a number of samples.
+fn samples(input: &str) -> PResult<'_, usize> {
+    let (rest, digits) = take_while1(is_digit).parse(input)?;
+    match digits.parse() {
+        Ok(samples) => Ok((rest, samples)),
+        Err(_) => Err(nom::Err::Error(SyntaxError::from_error_kind(
+            input,
+            ErrorKind::Digit,
+        ))),
+    }
+}
+
+/// Recognize the call of a sampled-value function, returning the signal, the number of samples
+/// if the function accepts one, and the clock.
+fn call<'a>(
+    input: &'a str,
+    function: &str,
+    with_samples: bool,
+) -> PResult<'a, (String, Option<usize>, Option<String>)> {
+    let (rest, _) = preceded(opt(whitespace), pair(tag(function), tag("("))).parse(input)?;
+    let (rest, id) =
+        expect(token(argument), &format!("signal after `{}(`", function)).parse(rest)?;
+    let (rest, samples) = if with_samples {
+        opt(preceded(tag(","), token(samples))).parse(rest)?
+    } else {
+        (rest, None)
+    };
+    let (rest, clock) = opt(preceded(
+        tag(","),
+        expect(token(argument), "clock after `,`"),
+    ))
+    .parse(rest)?;
+    let (rest, _) = expect(tag(")"), "`)`").parse(rest)?;
+
+    Ok((rest, (id, samples, clock)))
+}
+
+/// Recognize a sampled-value function.
+fn sampled(input: &str) -> PResult<'_, ExprAst> {
+    let functions = [
+        ("$rose", SampledFunction::Rose),
+        ("$fell", SampledFunction::Fell),
+        ("$stable", SampledFunction::Stable),
+        ("$changed", SampledFunction::Changed),
+    ];
+    for (name, function) in functions {
+        match call(input, name, false) {
+            Ok((rest, (id, _, clock))) => return Ok((rest, ExprAst::Sampled(function, id, clock))),
+            Err(nom::Err::Error(_)) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Err(nom::Err::Error(SyntaxError::from_error_kind(
+        input,
+        ErrorKind::Tag,
+    )))
+}
+
+/// Recognize a past value compared to a value.
+fn past(input: &str) -> PResult<'_, ExprAst> {
+    let (rest, (id, samples, clock)) = call(input, "$past", true)?;
+    let (rest, (operator, value)) = expect(
+        |input| compared_value(input, &["!=", "is not", "=", "is", "equals"]),
+        "comparison after `$past(...)`",
+    )
+    .parse(rest.trim_start())?;
+    let equal = matches!(operator, "=" | "is" | "equals");
+    Ok((
+        rest,
+        ExprAst::Past(id, samples.unwrap_or(1), clock, equal, value),
+    ))
+}
+
 /// Recognize a logical and.
-fn and(input: &str) -> IResult<&str, ExprAst> {
-    separated_pair(token(term), tag("and"), token(tier))
-        .parse(input)
-        .map(|(rest, (left, right))| (rest, ExprAst::And(Box::new(left), Box::new(right))))
+fn and(input: &str) -> PResult<'_, ExprAst> {
+    separated_pair(
+        token(term),
+        tag("and"),
+        expect(token(tier), "expression after `and`"),
+    )
+    .parse(input)
+    .map(|(rest, (left, right))| (rest, ExprAst::And(Box::new(left), Box::new(right))))
 }
 
 /// Recognize a logical nand.
-fn nand(input: &str) -> IResult<&str, ExprAst> {
-    separated_pair(token(term), tag("nand"), token(tier))
-        .parse(input)
-        .map(|(rest, (left, right))| {
-            let value = ExprAst::And(Box::new(left), Box::new(right));
+fn nand(input: &str) -> PResult<'_, ExprAst> {
+    separated_pair(
+        token(term),
+        tag("nand"),
+        expect(token(tier), "expression after `nand`"),
+    )
+    .parse(input)
+    .map(|(rest, (left, right))| {
+        let value = ExprAst::And(Box::new(left), Box::new(right));
 
-            (rest, ExprAst::Not(Box::new(value)))
-        })
+        (rest, ExprAst::Not(Box::new(value)))
+    })
 }
 
 /// Recognize a logical or.
-fn or(input: &str) -> IResult<&str, ExprAst> {
-    separated_pair(token(term), tag("or"), token(tier))
-        .parse(input)
-        .map(|(rest, (left, right))| (rest, ExprAst::Or(Box::new(left), Box::new(right))))
+fn or(input: &str) -> PResult<'_, ExprAst> {
+    separated_pair(
+        token(tier),
+        tag("or"),
+        expect(token(expr), "expression after `or`"),
+    )
+    .parse(input)
+    .map(|(rest, (left, right))| (rest, ExprAst::Or(Box::new(left), Box::new(right))))
 }
 
 /// Recognize an after duration.
-fn after(input: &str) -> IResult<&str, ExprAst> {
-    preceded(token(tag("after")), decimal)
+fn after(input: &str) -> PResult<'_, ExprAst> {
+    preceded(token(tag("after")), expect(time, "timestamp after `after`"))
         .parse(input)
         .map(|(rest, value)| (rest, ExprAst::After(value)))
 }
 
 /// Recognize a before duration.
-fn before(input: &str) -> IResult<&str, ExprAst> {
-    preceded(token(tag("before")), decimal)
-        .parse(input)
-        .map(|(rest, value)| (rest, ExprAst::Before(value)))
+fn before(input: &str) -> PResult<'_, ExprAst> {
+    preceded(
+        token(tag("before")),
+        expect(time, "timestamp after `before`"),
+    )
+    .parse(input)
+    .map(|(rest, value)| (rest, ExprAst::Before(value)))
 }
 
 #[cfg(test)]
@@ -273,11 +702,10 @@ mod test {
     use super::*;
     use crate::signaldb::BitValue::{self, High, HighZ, Low, Undefined};
     use nom::Err;
-    use nom::error::ErrorKind::{Tag, TakeWhileMN};
-    use nom::error::{Error, ErrorKind};
+    use nom::error::ErrorKind::{Digit, Tag, TakeWhile1, TakeWhileMN};
 
-    fn make_error<Output>(input: &str, code: ErrorKind) -> IResult<&str, Output> {
-        Err(Err::Error(Error { input, code }))
+    fn make_error<Output>(input: &str, code: ErrorKind) -> PResult<'_, Output> {
+        Err(Err::Error(SyntaxError::from_error_kind(input, code)))
     }
 
     fn make_id(id: &str) -> ValueAst {
@@ -305,12 +733,35 @@ mod test {
         assert_eq!(number("01"), Ok(("1", make_literal(0))));
         assert_eq!(number("42"), Ok(("", make_literal(42))));
 
+        assert_eq!(
+            number("b1??0"),
+            Ok((
+                "",
+                ValueAst::Wildcard(SignalValue::new(0b1000), SignalValue::new(0b0110))
+            ))
+        );
+        assert_eq!(
+            number("h1?"),
+            Ok((
+                "",
+                ValueAst::Wildcard(SignalValue::new(0x10), SignalValue::new(0x0f))
+            ))
+        );
+
         assert_eq!(number(""), make_error("", TakeWhileMN));
         assert_eq!(number(" "), make_error(" ", TakeWhileMN));
         assert_eq!(number("b2"), make_error("b2", TakeWhileMN));
         assert_eq!(number("$a"), make_error("$a", TakeWhileMN));
     }
 
+    #[test]
+    fn test_literal() {
+        assert_eq!(literal("h4a"), Some(SignalValue::new(74)));
+        assert_eq!(literal(" 42 "), Some(SignalValue::new(42)));
+        assert_eq!(literal("42z"), None);
+        assert_eq!(literal("$a"), None);
+    }
+
     #[test]
     fn test_identifier() {
         assert_eq!(identifier("$a"), Ok(("", make_id("a"))));
@@ -432,16 +883,175 @@ mod test {
 
     #[test]
     fn test_after() {
-        assert_eq!(after("after 12 foo"), Ok((" foo", ExprAst::After(12))));
+        assert_eq!(
+            after("after 12 foo"),
+            Ok((" foo", ExprAst::After(TimeAst::Ticks(12))))
+        );
 
         assert_eq!(after(""), make_error("", Tag));
         assert_eq!(after(" "), make_error("", Tag));
     }
 
+    #[test]
+    fn test_time() {
+        use crate::signaldb::{Scale, Timestamp};
+        let t = |value, scale| TimeAst::Timestamp(Timestamp::new(value, scale));
+
+        assert_eq!(time("42 foo"), Ok((" foo", TimeAst::Ticks(42))));
+        assert_eq!(time("10ns"), Ok(("", t(10, Scale::Nanosecond))));
+        assert_eq!(time("1.5us foo"), Ok((" foo", t(1500, Scale::Nanosecond))));
+        assert_eq!(time("250ps"), Ok(("", t(250, Scale::Picosecond))));
+        assert_eq!(time("3s"), Ok(("", t(3, Scale::Second))));
+        assert_eq!(time("10 ns"), Ok((" ns", TimeAst::Ticks(10))));
+
+        assert_eq!(time("1.5"), make_error("1.5", Digit));
+        assert_eq!(time("0.5fs"), make_error("0.5fs", Digit));
+        assert_eq!(time("ns"), make_error("ns", TakeWhile1));
+    }
+
+    #[test]
+    fn test_known() {
+        assert_eq!(
+            known("$a is unknown foo"),
+            Ok((" foo", ExprAst::Unknown("a".to_string())))
+        );
+        assert_eq!(
+            known("$a is  known"),
+            Ok((
+                "",
+                ExprAst::Not(Box::new(ExprAst::Unknown("a".to_string())))
+            ))
+        );
+        assert_eq!(known("$a is 1"), make_error("1", Tag));
+    }
+
+    #[test]
+    fn test_has() {
+        assert_eq!(
+            has("$a has z"),
+            Ok(("", ExprAst::Has("a".to_string(), HighZ)))
+        );
+        assert_eq!(
+            has("$a has x foo"),
+            Ok(("foo", ExprAst::Has("a".to_string(), BitValue::Invalid)))
+        );
+        assert!(matches!(has("$a has 2"), Err(Err::Failure(_))));
+        assert_eq!(has("$a = 1"), make_error("= 1", Tag));
+    }
+
+    #[test]
+    fn test_matches() {
+        assert_eq!(
+            matches("$state matches /ERR.*/ foo"),
+            Ok((
+                "foo",
+                ExprAst::Matches("state".to_string(), Pattern::new("ERR.*").unwrap())
+            ))
+        );
+        assert_eq!(
+            matches(r"$path matches /^\/dev\/tty\d+$/"),
+            Ok((
+                "",
+                ExprAst::Matches("path".to_string(), Pattern::new(r"^/dev/tty\d+$").unwrap())
+            ))
+        );
+        assert!(matches!(matches("$a matches /ERR(/"), Err(Err::Failure(_))));
+        assert!(matches!(matches("$a matches /ERR"), Err(Err::Failure(_))));
+        assert!(matches!(matches("$a matches ERR"), Err(Err::Failure(_))));
+        assert_eq!(matches("$a = 1"), make_error("= 1", Tag));
+    }
+
+    #[test]
+    fn test_contains() {
+        assert_eq!(
+            contains(r#"$msg contains "timeout" foo"#),
+            Ok((
+                "foo",
+                ExprAst::Contains("msg".to_string(), "timeout".to_string())
+            ))
+        );
+        assert_eq!(
+            contains(r#"$msg contains "say \"hi\" \\o/""#),
+            Ok((
+                "",
+                ExprAst::Contains("msg".to_string(), r#"say "hi" \o/"#.to_string())
+            ))
+        );
+        assert!(matches!(
+            contains(r#"$a contains "foo"#),
+            Err(Err::Failure(_))
+        ));
+        assert!(matches!(contains("$a contains foo"), Err(Err::Failure(_))));
+    }
+
+    #[test]
+    fn test_sampled() {
+        assert_eq!(
+            sampled("$rose($a) foo"),
+            Ok((
+                " foo",
+                ExprAst::Sampled(SampledFunction::Rose, "a".to_string(), None)
+            ))
+        );
+        assert_eq!(
+            sampled(" $stable( $a , $clk )"),
+            Ok((
+                "",
+                ExprAst::Sampled(
+                    SampledFunction::Stable,
+                    "a".to_string(),
+                    Some("clk".to_string())
+                )
+            ))
+        );
+        assert_eq!(sampled("$rosea"), make_error("$rosea", Tag));
+        assert!(matches!(sampled("$fell($a"), Err(Err::Failure(_))));
+        assert!(matches!(sampled("$changed($a, 2)"), Err(Err::Failure(_))));
+    }
+
+    #[test]
+    fn test_past() {
+        assert_eq!(
+            past("$past($a) = 1"),
+            Ok((
+                "",
+                ExprAst::Past("a".to_string(), 1, None, true, make_literal(1))
+            ))
+        );
+        assert_eq!(
+            past("$past($a, 2) is $b"),
+            Ok((
+                "",
+                ExprAst::Past("a".to_string(), 2, None, true, make_id("b"))
+            ))
+        );
+        assert_eq!(
+            past("$past($a, $clk) != 3"),
+            Ok((
+                "",
+                ExprAst::Past(
+                    "a".to_string(),
+                    1,
+                    Some("clk".to_string()),
+                    false,
+                    make_literal(3)
+                )
+            ))
+        );
+        assert!(matches!(past("$past($a)"), Err(Err::Failure(_))));
+        assert_eq!(past("$a = 1"), make_error("$a = 1", Tag));
+    }
+
     #[test]
     fn test_before() {
-        assert_eq!(before("before 2"), Ok(("", ExprAst::Before(2))));
-        assert_eq!(before("before 23 foo"), Ok((" foo", ExprAst::Before(23))));
+        assert_eq!(
+            before("before 2"),
+            Ok(("", ExprAst::Before(TimeAst::Ticks(2))))
+        );
+        assert_eq!(
+            before("before 23 foo"),
+            Ok((" foo", ExprAst::Before(TimeAst::Ticks(23))))
+        );
 
         assert_eq!(before(""), make_error("", Tag));
         assert_eq!(before(" "), make_error("", Tag));
@@ -455,7 +1065,7 @@ mod test {
                 "",
                 ExprAst::And(
                     Box::new(ExprAst::Equal("a".to_string(), make_literal(8))),
-                    Box::new(ExprAst::Before(2))
+                    Box::new(ExprAst::Before(TimeAst::Ticks(2)))
                 )
             ))
         );
@@ -482,7 +1092,7 @@ mod test {
                 "",
                 ExprAst::Or(
                     Box::new(ExprAst::Equal("a".to_string(), make_literal(8))),
-                    Box::new(ExprAst::Before(2))
+                    Box::new(ExprAst::Before(TimeAst::Ticks(2)))
                 )
             ))
         );
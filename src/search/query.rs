@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: MIT
+use super::expr::{ExprAst, ParseError};
+use super::types::Search;
+use crate::signaldb::{SignalDB, TimeDescr, Timestamp};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Search expression parsed once and run against any number of `SignalDB`
+///
+/// # Example
+///
+/// ```
+/// use dwfv::search::{ExprAst, Query};
+/// use dwfv::signaldb::{Scale, SignalDB, TimeDescr, Timestamp};
+/// let vcd = std::io::Cursor::new("$scope module top $end
+/// $var wire 1 0 valid $end
+/// $var wire 1 1 ready $end
+/// $upscope $end
+/// $enddefinitions $end
+/// #0
+/// 00
+/// 01
+/// #10
+/// 10
+/// #20
+/// 11
+/// #30
+/// 00
+/// 01
+/// #40
+/// 10
+/// #50
+/// ");
+///
+/// let db = SignalDB::from_vcd(vcd).unwrap();
+/// let t = |v| Timestamp::new(v, Scale::Picosecond);
+///
+/// let query = Query::parse("$0 = 1 and $1 = 0").unwrap();
+/// assert_eq!(query.ast().signal_ids(), vec!["0", "1"]);
+/// assert_eq!(
+///     query.run(&db).unwrap(),
+///     vec![TimeDescr::Period(t(10), t(20)), TimeDescr::Period(t(40), t(50))]
+/// );
+/// assert_eq!(
+///     query.run_between(&db, t(15), t(45)).unwrap(),
+///     vec![TimeDescr::Period(t(15), t(20)), TimeDescr::Period(t(40), t(45))]
+/// );
+///
+/// let query: Query = "$1 <- 1".parse().unwrap();
+/// assert_eq!(query.run(&db).unwrap(), vec![TimeDescr::Point(t(20))]);
+///
+/// assert!(Query::parse("0 = 1").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    expr: String,
+    ast: ExprAst,
+}
+
+impl Query {
+    /// Parse a search expression.
+    pub fn parse(expr: &str) -> Result<Query, ParseError> {
+        Ok(Query {
+            expr: expr.to_string(),
+            ast: expr.parse()?,
+        })
+    }
+
+    /// Get the abstract syntax tree of the expression.
+    pub fn ast(&self) -> &ExprAst {
+        &self.ast
+    }
+
+    /// Find the points and periods of time at which the expression is true in the whole
+    /// `SignalDB`. A period still open at the end of the dump ends at the last timestamp.
+    pub fn run(&self, signaldb: &SignalDB) -> Result<Vec<TimeDescr>, Box<dyn Error>> {
+        let mut search = Search::from_ast(self.ast.clone());
+        search.search_all(signaldb)?;
+        search.into_findings(signaldb, signaldb.get_time())
+    }
+
+    /// Find the points and periods of time at which the expression is true from `begin` included
+    /// to `end` excluded. The periods are clipped to the time window.
+    pub fn run_between(
+        &self,
+        signaldb: &SignalDB,
+        begin: Timestamp,
+        end: Timestamp,
+    ) -> Result<Vec<TimeDescr>, Box<dyn Error>> {
+        let mut search = Search::from_ast(self.ast.clone());
+        for timestamp in search.timestamps(signaldb, begin, end)? {
+            search.search_at(signaldb, timestamp)?
+        }
+        search.into_findings(signaldb, end)
+    }
+}
+
+impl FromStr for Query {
+    type Err = ParseError;
+
+    fn from_str(expr: &str) -> Result<Query, ParseError> {
+        Query::parse(expr)
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
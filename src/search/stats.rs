@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: MIT
+use crate::signaldb::{TimeDescr, Timestamp};
+use std::io;
+
+/// Statistics about the findings of a search expression
+///
+/// # Example
+///
+/// ```
+/// use dwfv::search::FindingsStats;
+/// use dwfv::signaldb::{Scale, TimeDescr, Timestamp};
+/// let t = |v| Timestamp::new(v, Scale::Nanosecond);
+/// let stats = FindingsStats::new(&[
+///     TimeDescr::Period(t(10), t(20)),
+///     TimeDescr::Point(t(25)),
+///     TimeDescr::Period(t(30), t(60)),
+/// ]);
+///
+/// assert_eq!(stats.count(), 3);
+/// assert_eq!(stats.periods(), 2);
+/// assert_eq!(stats.points(), 1);
+/// assert_eq!(stats.covered(), Some(t(40)));
+/// assert_eq!(stats.min(), Some(t(10)));
+/// assert_eq!(stats.max(), Some(t(30)));
+/// assert_eq!(stats.mean(), Some(t(20)));
+///
+/// let mut buf = Vec::new();
+/// stats.format(&mut buf);
+/// assert_eq!(
+///     String::from_utf8(buf).unwrap(),
+///     "findings: 3 (2 periods, 1 points)\n\
+///      covered: 40ns\n\
+///      min: 10ns, max: 30ns, mean: 20ns\n"
+/// );
+///
+/// let stats = FindingsStats::new(&[TimeDescr::Point(t(25))]);
+/// assert_eq!(stats.covered(), None);
+/// assert_eq!(stats.mean(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FindingsStats {
+    points: usize,
+    lengths: Vec<Timestamp>,
+}
+
+impl FindingsStats {
+    /// Compute the statistics of some findings.
+    pub fn new(findings: &[TimeDescr]) -> FindingsStats {
+        let mut points = 0;
+        let mut lengths = Vec::new();
+        for finding in findings {
+            match finding {
+                TimeDescr::Point(_) => points += 1,
+                TimeDescr::Period(begin, end) => lengths.push(*end - *begin),
+            }
+        }
+        lengths.sort();
+        FindingsStats { points, lengths }
+    }
+
+    /// Get the number of findings.
+    pub fn count(&self) -> usize {
+        self.points + self.lengths.len()
+    }
+
+    /// Get the number of points of time found.
+    pub fn points(&self) -> usize {
+        self.points
+    }
+
+    /// Get the number of periods of time found.
+    pub fn periods(&self) -> usize {
+        self.lengths.len()
+    }
+
+    /// Get the total time covered by the periods.
+    pub fn covered(&self) -> Option<Timestamp> {
+        self.lengths.iter().copied().reduce(|a, b| a + b)
+    }
+
+    /// Get the length of the shortest period.
+    pub fn min(&self) -> Option<Timestamp> {
+        self.lengths.first().copied()
+    }
+
+    /// Get the length of the longest period.
+    pub fn max(&self) -> Option<Timestamp> {
+        self.lengths.last().copied()
+    }
+
+    /// Get the mean length of the periods, rounded down to the time scale of the dump.
+    pub fn mean(&self) -> Option<Timestamp> {
+        Some(self.covered()? / self.lengths.len() as i64)
+    }
+
+    /// Format the statistics.
+    pub fn format(&self, output: &mut dyn io::Write) {
+        let _ = writeln!(
+            output,
+            "findings: {} ({} periods, {} points)",
+            self.count(),
+            self.periods(),
+            self.points()
+        );
+        let (Some(covered), Some(min), Some(max), Some(mean)) =
+            (self.covered(), self.min(), self.max(), self.mean())
+        else {
+            return;
+        };
+        let _ = writeln!(output, "covered: {}", covered);
+        let _ = writeln!(output, "min: {}, max: {}, mean: {}", min, max, mean);
+    }
+}
@@ -1,13 +1,14 @@
 // SPDX-License-Identifier: MIT
-use super::expr::{ExprAst, ValueAst};
-use crate::signaldb::{SignalDB, SignalValue, TimeDescr, Timestamp};
+use super::expr::{ExprAst, LengthAst, SampledFunction, TimeAst, ValueAst};
+use crate::signaldb::{BitValue, SignalDB, SignalValue, TimeDescr, Timestamp};
+use std::collections::BTreeSet;
 use std::error::Error;
 use std::io;
 use std::ops::{BitAnd, BitOr};
 
 pub(crate) struct Search {
     findings: Vec<TimeDescr>,
-    expr: ExprAst,
+    expr: Option<ExprAst>,
     current_period: Option<Timestamp>,
     cursor: Option<Timestamp>,
 }
@@ -70,7 +71,7 @@ impl BitAnd for EvalResult {
 }
 
 /// Summary of findings within a time period
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FindingsSummary {
     /// No findings in the time period
     Nothing,
@@ -88,13 +89,47 @@ pub enum FindingsSummary {
 
 impl Search {
     pub(crate) fn new(expr: &str) -> Result<Search, Box<dyn Error>> {
-        let search = Search {
-            expr: ExprAst::from_str(expr)?,
+        Ok(Search::from_ast(expr.parse()?))
+    }
+
+    pub(crate) fn from_ast(expr: ExprAst) -> Search {
+        Search {
+            expr: Some(expr),
             findings: Vec::new(),
             current_period: None,
             cursor: Some(Timestamp::origin()),
-        };
-        Ok(search)
+        }
+    }
+
+    pub(crate) fn from_findings(mut findings: Vec<TimeDescr>) -> Search {
+        findings.sort_by_key(|finding| match finding {
+            TimeDescr::Point(t) => *t,
+            TimeDescr::Period(begin, _) => *begin,
+        });
+
+        let mut merged: Vec<TimeDescr> = Vec::new();
+        for finding in findings {
+            let (begin, end) = match finding {
+                TimeDescr::Point(t) => (t, t),
+                TimeDescr::Period(begin, end) => (begin, end),
+            };
+            match merged.last_mut() {
+                Some(last) if matches!(*last, TimeDescr::Point(t) if t == begin) => *last = finding,
+                Some(TimeDescr::Period(_, last_end)) if begin < *last_end => {
+                    if end > *last_end {
+                        *last_end = end
+                    }
+                }
+                _ => merged.push(finding),
+            }
+        }
+
+        Search {
+            expr: None,
+            findings: merged,
+            current_period: None,
+            cursor: None,
+        }
     }
 
     pub(crate) fn eval_value_at(
@@ -106,10 +141,98 @@ impl Search {
         let res = match value {
             ValueAst::Literal(v) => v.clone(),
             ValueAst::Id(id) => signaldb.value_at(id, timestamp)?,
+            ValueAst::Wildcard(v, _) => v.clone(),
         };
         Ok(res)
     }
 
+    /// Check whether a value matches a value of the expression, ignoring its don't-care bits.
+    fn matches_value_at(
+        &self,
+        value: &SignalValue,
+        expected: &ValueAst,
+        signaldb: &SignalDB,
+        timestamp: Timestamp,
+    ) -> Result<bool, Box<dyn Error>> {
+        let matches = match expected {
+            ValueAst::Wildcard(pattern, ignored) => value.eq_ignoring(pattern, ignored),
+            _ => *value == self.eval_value_at(expected, signaldb, timestamp)?,
+        };
+        Ok(matches)
+    }
+
+    /// Get the value of a signal `samples` samples before the current one. With a clock, the
+    /// signal is sampled just before its rising edges, and there is no sample if the clock does
+    /// not rise at `timestamp`. Otherwise, the samples are the successive values of the signal.
+    fn sample_at(
+        &self,
+        signaldb: &SignalDB,
+        signal_id: &str,
+        clock: Option<&str>,
+        timestamp: Timestamp,
+        samples: usize,
+    ) -> Result<Option<SignalValue>, Box<dyn Error>> {
+        let Some(clock) = clock else {
+            return Ok(signaldb.past_value_at(signal_id, timestamp, samples)?);
+        };
+        match signaldb.event_at(clock, timestamp)? {
+            Some(value) if value != SignalValue::new(0) => {}
+            _ => return Ok(None),
+        }
+        let mut edge = timestamp;
+        for _ in 0..samples {
+            match signaldb.get_previous_rising_edge(clock, edge)? {
+                Some(previous) => edge = previous,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(signaldb.value_at(signal_id, edge - edge.derive(1))?))
+    }
+
+    fn eval_sampled_at(
+        &self,
+        function: SampledFunction,
+        signal_id: &str,
+        clock: Option<&str>,
+        signaldb: &SignalDB,
+        timestamp: Timestamp,
+    ) -> Result<EvalResult, Box<dyn Error>> {
+        let (current, previous) = if clock.is_some() {
+            (
+                self.sample_at(signaldb, signal_id, clock, timestamp, 0)?,
+                self.sample_at(signaldb, signal_id, clock, timestamp, 1)?,
+            )
+        } else {
+            // The first value of the signal has no previous sample.
+            let previous = match signaldb.get_first_event(signal_id)? {
+                Some(first) if first < timestamp => {
+                    Some(signaldb.value_at(signal_id, timestamp - timestamp.derive(1))?)
+                }
+                _ => None,
+            };
+            (Some(signaldb.value_at(signal_id, timestamp)?), previous)
+        };
+        let result = match (current, previous) {
+            (Some(current), Some(previous)) => match function {
+                SampledFunction::Rose => {
+                    current.get_bit(0) == BitValue::High && previous.get_bit(0) == BitValue::Low
+                }
+                SampledFunction::Fell => {
+                    current.get_bit(0) == BitValue::Low && previous.get_bit(0) == BitValue::High
+                }
+                SampledFunction::Stable => current == previous,
+                SampledFunction::Changed => current != previous,
+            },
+            _ => false,
+        };
+        // Without a clock, a signal is stable between its changes.
+        let ty = match (function, clock) {
+            (SampledFunction::Stable, None) => ExprType::Level,
+            _ => ExprType::Transition,
+        };
+        Ok(EvalResult { result, ty })
+    }
+
     fn eval_at(
         &self,
         expr: &ExprAst,
@@ -118,19 +241,45 @@ impl Search {
     ) -> Result<EvalResult, Box<dyn Error>> {
         let res = match expr {
             ExprAst::Equal(id, v) => EvalResult {
-                result: signaldb.value_at(id, timestamp)?
-                    == self.eval_value_at(v, signaldb, timestamp)?,
+                result: self.matches_value_at(
+                    &signaldb.value_at(id, timestamp)?,
+                    v,
+                    signaldb,
+                    timestamp,
+                )?,
                 ty: ExprType::Level,
             },
             ExprAst::Transition(id, v) => EvalResult {
                 result: {
                     match signaldb.event_at(id, timestamp)? {
-                        Some(evt) => evt == self.eval_value_at(v, signaldb, timestamp)?,
+                        Some(evt) => self.matches_value_at(&evt, v, signaldb, timestamp)?,
                         None => false,
                     }
                 },
                 ty: ExprType::Transition,
             },
+            ExprAst::Unknown(id) => EvalResult {
+                result: signaldb.value_at(id, timestamp)?.is_invalid(),
+                ty: ExprType::Level,
+            },
+            ExprAst::Has(id, bit) => EvalResult {
+                result: signaldb.value_at(id, timestamp)?.has_bit(*bit),
+                ty: ExprType::Level,
+            },
+            ExprAst::Matches(id, pattern) => EvalResult {
+                result: match signaldb.value_at(id, timestamp)? {
+                    SignalValue::Symbol(symbol) => pattern.is_match(&symbol),
+                    _ => false,
+                },
+                ty: ExprType::Level,
+            },
+            ExprAst::Contains(id, string) => EvalResult {
+                result: match signaldb.value_at(id, timestamp)? {
+                    SignalValue::Symbol(symbol) => symbol.contains(string.as_str()),
+                    _ => false,
+                },
+                ty: ExprType::Level,
+            },
             ExprAst::AnyTransition(id) => EvalResult {
                 result: signaldb.event_at(id, timestamp)?.is_some(),
                 ty: ExprType::Transition,
@@ -163,51 +312,151 @@ impl Search {
                 EvalResult { result, ty }
             }
             ExprAst::After(t) => EvalResult {
-                result: timestamp > timestamp.derive(*t),
+                result: timestamp > t.to_timestamp(timestamp),
                 ty: ExprType::Level,
             },
             ExprAst::Before(t) => EvalResult {
-                result: timestamp < timestamp.derive(*t),
+                result: timestamp < t.to_timestamp(timestamp),
                 ty: ExprType::Level,
             },
+            ExprAst::Sampled(function, id, clock) => {
+                self.eval_sampled_at(*function, id, clock.as_deref(), signaldb, timestamp)?
+            }
+            ExprAst::Past(id, samples, clock, equal, v) => EvalResult {
+                result: match self.sample_at(signaldb, id, clock.as_deref(), timestamp, *samples)? {
+                    Some(past) => self.matches_value_at(&past, v, signaldb, timestamp)? == *equal,
+                    None => false,
+                },
+                ty: if clock.is_some() {
+                    ExprType::Transition
+                } else {
+                    ExprType::Level
+                },
+            },
+            // The length of the findings is only checked once they are complete.
+            ExprAst::For(e, _, _) => self.eval_at(e, signaldb, timestamp)?,
         };
         Ok(res)
     }
 
+    /// Collect the times compared to the current time by the expression.
+    fn collect_times<'a>(expr: &'a ExprAst, times: &mut Vec<&'a TimeAst>) {
+        match expr {
+            ExprAst::After(t) | ExprAst::Before(t) => times.push(t),
+            ExprAst::Not(e) | ExprAst::For(e, _, _) => Search::collect_times(e, times),
+            ExprAst::And(le, re) | ExprAst::Or(le, re) => {
+                Search::collect_times(le, times);
+                Search::collect_times(re, times)
+            }
+            _ => {}
+        }
+    }
+
+    /// Get the timestamps, from `begin` included to `end` excluded, at which the expression has
+    /// to be evaluated, i.e. `begin`, the change points of the signals it references and the
+    /// timestamps at which its `after` and `before` conditions change. The expression keeps the
+    /// same value between two of them.
+    pub(crate) fn timestamps(
+        &self,
+        signaldb: &SignalDB,
+        begin: Timestamp,
+        end: Timestamp,
+    ) -> Result<Vec<Timestamp>, Box<dyn Error>> {
+        let Some(expr) = &self.expr else {
+            return Ok(Vec::new());
+        };
+        if begin >= end {
+            return Ok(Vec::new());
+        }
+
+        let mut timestamps = BTreeSet::new();
+        timestamps.insert(begin);
+        timestamps.extend(signaldb.change_points(&expr.signal_ids(), begin, end)?);
+
+        let mut times = Vec::new();
+        Search::collect_times(expr, &mut times);
+        // The timestamps of the dump all share the scale of the last one.
+        let reference = signaldb.get_last_timestamp();
+        for t in times {
+            timestamps.extend(
+                signaldb
+                    .get_timestamps_from(t.to_timestamp(reference), 2)
+                    .into_iter()
+                    .filter(|timestamp| begin <= *timestamp && *timestamp < end),
+            )
+        }
+        Ok(timestamps.into_iter().collect())
+    }
+
+    /// Get all the timestamps of the `SignalDB` at which the expression has to be evaluated.
+    pub(crate) fn all_timestamps(
+        &self,
+        signaldb: &SignalDB,
+    ) -> Result<Vec<Timestamp>, Box<dyn Error>> {
+        let end = signaldb.get_last_timestamp();
+        let mut timestamps = self.timestamps(signaldb, Timestamp::origin(), end)?;
+        timestamps.push(end);
+        Ok(timestamps)
+    }
+
     pub(crate) fn search_all(&mut self, signaldb: &SignalDB) -> Result<(), Box<dyn Error>> {
+        self.search_first(signaldb, usize::MAX)
+    }
+
+    /// Search in the whole `SignalDB`, stopping as soon as `count` findings are found.
+    pub(crate) fn search_first(
+        &mut self,
+        signaldb: &SignalDB,
+        count: usize,
+    ) -> Result<(), Box<dyn Error>> {
         self.findings.clear();
         self.current_period = None;
-        for timestamp in signaldb.get_timestamps() {
+        for timestamp in self.all_timestamps(signaldb)? {
+            if self.findings.len() >= count {
+                break;
+            }
             self.search_at(signaldb, timestamp)?
         }
+        self.findings.truncate(count);
         self.finish();
         Ok(())
     }
 
+    pub(crate) fn findings(&self) -> &[TimeDescr] {
+        &self.findings
+    }
+
     pub(crate) fn search_at(
         &mut self,
         signaldb: &SignalDB,
         timestamp: Timestamp,
     ) -> Result<(), Box<dyn Error>> {
-        if let Some(cursor) = self.cursor {
-            if timestamp < cursor {
-                return Ok(());
-            }
+        if let Some(cursor) = self.cursor
+            && timestamp < cursor
+        {
+            return Ok(());
         }
-        let res = self.eval_at(&self.expr, signaldb, timestamp)?;
+        let res = match &self.expr {
+            Some(expr) => self.eval_at(expr, signaldb, timestamp)?,
+            None => return Ok(()),
+        };
         match res.ty {
             ExprType::Transition => {
-                if res.result && self.current_period.is_none() {
+                if res.result
+                    && self.current_period.is_none()
+                    && self.has_valid_length(signaldb, timestamp, timestamp)?
+                {
                     self.findings.push(TimeDescr::Point(timestamp))
                 }
             }
             ExprType::Level => {
                 if res.result && self.current_period.is_none() {
                     self.current_period = Some(timestamp)
-                } else if !res.result && self.current_period.is_some() {
-                    self.findings
-                        .push(TimeDescr::Period(self.current_period.unwrap(), timestamp));
-                    self.current_period = None;
+                } else if !res.result
+                    && let Some(begin) = self.current_period.take()
+                    && self.has_valid_length(signaldb, begin, timestamp)?
+                {
+                    self.findings.push(TimeDescr::Period(begin, timestamp))
                 }
             }
         };
@@ -219,6 +468,44 @@ impl Search {
         self.cursor = None
     }
 
+    /// Check whether a finding satisfies the constraint on its length, if any.
+    fn has_valid_length(
+        &self,
+        signaldb: &SignalDB,
+        begin: Timestamp,
+        end: Timestamp,
+    ) -> Result<bool, Box<dyn Error>> {
+        let valid = match &self.expr {
+            Some(ExprAst::For(_, comparison, LengthAst::Time(length))) => {
+                comparison.compare(end - begin, length.to_timestamp(begin))
+            }
+            Some(ExprAst::For(_, comparison, LengthAst::Cycles(length, clock))) => {
+                let cycles = signaldb.count_rising_edges(clock, begin, end)?;
+                comparison.compare(cycles as i64, *length)
+            }
+            _ => true,
+        };
+        Ok(valid)
+    }
+
+    /// Get the findings, the period still open at `end` being closed at `end`.
+    pub(crate) fn into_findings(
+        mut self,
+        signaldb: &SignalDB,
+        end: Timestamp,
+    ) -> Result<Vec<TimeDescr>, Box<dyn Error>> {
+        if let Some(begin) = self.current_period.take()
+            && self.has_valid_length(signaldb, begin, end)?
+        {
+            self.findings.push(if begin < end {
+                TimeDescr::Period(begin, end)
+            } else {
+                TimeDescr::Point(begin)
+            })
+        }
+        Ok(self.findings)
+    }
+
     pub(crate) fn format_findings(&self, output: &mut dyn io::Write) {
         for timestamp in &self.findings {
             let _ = writeln!(output, "{}", timestamp);
@@ -389,4 +676,247 @@ mod test {
         let mut _db = SignalDB::new();
         let mut _search = Search::new("$A");
     }
+
+    fn find(signaldb: &SignalDB, expr: &str) -> Vec<TimeDescr> {
+        let mut search = Search::new(expr).unwrap();
+        for timestamp in signaldb.get_timestamps() {
+            search.search_at(signaldb, timestamp).unwrap()
+        }
+        search.into_findings(signaldb, signaldb.get_time()).unwrap()
+    }
+
+    #[test]
+    fn event_driven() {
+        let vcd = io::Cursor::new(
+            "$timescale 1ns $end
+$scope module top $end
+$var wire 1 c clk $end
+$var wire 1 v valid $end
+$var wire 4 d data $end
+$var wire 1 n noise $end
+$upscope $end
+$enddefinitions $end
+#0
+0c
+0v
+b0 d
+0n
+#3
+1n
+#5
+1c
+#7
+0n
+#10
+0c
+1v
+b1 d
+#12
+1n
+#15
+1c
+#18
+0n
+#20
+0c
+0v
+b10 d
+#25
+1c
+#27
+1n
+#30
+0c
+#35
+1c
+#38
+0n
+",
+        );
+        let db = SignalDB::from_vcd(vcd).unwrap();
+
+        for expr in [
+            "$v = 1",
+            "$d <- 1 or $v = 0",
+            "$v and $d = 2",
+            "$v = 1 nand $d = 1",
+            "$stable($d)",
+            "$changed($d)",
+            "$rose($v, $c)",
+            "$past($d) = 1",
+            "$past($d, $c) != 1",
+            "$d = 2 and after 26",
+            "$d = 2 and after 26ns",
+            "before 2.5ns or after 0.02us",
+            "before 13 or $v = 1",
+            "$c = 1 for >= 5",
+            "$d = 0 for > 1 cycles of $c",
+        ] {
+            let mut search = Search::new(expr).unwrap();
+            search.search_all(&db).unwrap();
+            assert_eq!(
+                search.into_findings(&db, db.get_time()).unwrap(),
+                find(&db, expr),
+                "{}",
+                expr
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_and_wildcards() {
+        let vcd = io::Cursor::new(
+            "$scope module top $end
+$var wire 4 b bus $end
+$upscope $end
+$enddefinitions $end
+#0
+bxxxx b
+#10
+b10z1 b
+#20
+b1001 b
+#30
+b1110 b
+#40
+",
+        );
+        let db = SignalDB::from_vcd(vcd).unwrap();
+        let t = |value| Timestamp::new(value, crate::signaldb::Scale::Picosecond);
+
+        assert_eq!(
+            find(&db, "$b is unknown"),
+            vec![TimeDescr::Period(t(0), t(20))]
+        );
+        assert_eq!(
+            find(&db, "$b is known"),
+            vec![TimeDescr::Period(t(20), t(40))]
+        );
+        assert_eq!(find(&db, "$b has z"), vec![TimeDescr::Period(t(10), t(20))]);
+        assert_eq!(
+            find(&db, "$b = b1?01"),
+            vec![TimeDescr::Period(t(20), t(30))]
+        );
+        assert_eq!(
+            find(&db, "$b <- b1??1"),
+            vec![TimeDescr::Point(t(10)), TimeDescr::Point(t(20))]
+        );
+        assert_eq!(find(&db, "$b = h?"), vec![TimeDescr::Period(t(0), t(40))]);
+    }
+
+    #[test]
+    fn symbols() {
+        let vcd = io::Cursor::new(
+            "$scope module top $end
+$var string 1 s state $end
+$var string 1 m msg $end
+$var wire 4 b bus $end
+$upscope $end
+$enddefinitions $end
+#0
+sIDLE s
+sok m
+b0 b
+#10
+sERR_PARITY s
+sread_timeout m
+#20
+sBUSY s
+sdone m
+#30
+sERR_TIMEOUT s
+#40
+",
+        );
+        let db = SignalDB::from_vcd(vcd).unwrap();
+        let t = |value| Timestamp::new(value, crate::signaldb::Scale::Picosecond);
+
+        assert_eq!(
+            find(&db, "$s matches /^ERR_/"),
+            vec![
+                TimeDescr::Period(t(10), t(20)),
+                TimeDescr::Period(t(30), t(40))
+            ]
+        );
+        assert_eq!(
+            find(&db, "$s matches /^(IDLE|BUSY)$/"),
+            vec![
+                TimeDescr::Period(t(0), t(10)),
+                TimeDescr::Period(t(20), t(30))
+            ]
+        );
+        assert_eq!(
+            find(&db, "$m contains \"timeout\" or $s contains \"TIMEOUT\""),
+            vec![
+                TimeDescr::Period(t(10), t(20)),
+                TimeDescr::Period(t(30), t(40))
+            ]
+        );
+        assert_eq!(find(&db, "$b matches /.*/"), vec![]);
+        assert_eq!(find(&db, "$b contains \"\""), vec![]);
+    }
+
+    #[test]
+    fn sampled() {
+        let vcd = io::Cursor::new(
+            "$timescale 1ns $end
+$scope module top $end
+$var wire 1 c clk $end
+$var wire 1 v valid $end
+$var wire 4 d data $end
+$upscope $end
+$enddefinitions $end
+#0
+0c
+0v
+b0 d
+#5
+1c
+#10
+0c
+1v
+b1 d
+#15
+1c
+#20
+0c
+0v
+b10 d
+#25
+1c
+#30
+0c
+#35
+1c
+",
+        );
+        let db = SignalDB::from_vcd(vcd).unwrap();
+        let t = |value| Timestamp::new(value, crate::signaldb::Scale::Nanosecond);
+
+        assert_eq!(find(&db, "$rose($v)"), vec![TimeDescr::Point(t(10))]);
+        assert_eq!(find(&db, "$rose($v, $c)"), vec![TimeDescr::Point(t(15))]);
+        assert_eq!(find(&db, "$fell($v, $c)"), vec![TimeDescr::Point(t(25))]);
+        assert_eq!(find(&db, "$stable($d, $c)"), vec![TimeDescr::Point(t(35))]);
+        assert_eq!(
+            find(&db, "$changed($d)"),
+            vec![TimeDescr::Point(t(10)), TimeDescr::Point(t(20))]
+        );
+        assert_eq!(
+            find(&db, "$past($d, 2, $c) = 1"),
+            vec![TimeDescr::Point(t(35))]
+        );
+        assert_eq!(
+            find(&db, "$past($d) = 1"),
+            vec![TimeDescr::Period(t(20), t(35))]
+        );
+        assert_eq!(
+            find(&db, "$past($d, $c) != 1"),
+            vec![TimeDescr::Point(t(15)), TimeDescr::Point(t(35))]
+        );
+        assert_eq!(
+            find(&db, "$past($d, 2) != 5"),
+            vec![TimeDescr::Period(t(20), t(35))]
+        );
+        assert_eq!(find(&db, "$past($d, 3) != 5"), vec![]);
+    }
 }
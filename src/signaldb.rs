@@ -8,7 +8,8 @@ mod value;
 
 pub use self::async_db::AsyncSignalDB;
 pub use self::db::SignalDB;
+pub(crate) use self::db::SignalNotFound;
 pub use self::signal::Signal;
-pub use self::time::{Scale, TimeDescr, Timestamp};
+pub use self::time::{InvalidTimestamp, Scale, TimeDescr, Timestamp};
 pub use self::value::{BitValue, SignalValue};
 pub use crate::search::FindingsSummary;
@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: MIT
 use super::db::SignalDB;
+use crate::analysis;
 use std::default::Default;
 use std::io;
 use std::sync::Arc;
@@ -107,7 +108,15 @@ impl AsyncSignalDB {
             if let Err(e) = db_search.search_init(&expr) {
                 db_search.set_status(format!("Cannot initialize search: {}: {}", expr, e).as_str())
             };
-            for timestamp in db_search.get_timestamps() {
+            let timestamps = match db_search.search_timestamps(&expr) {
+                Ok(timestamps) => timestamps,
+                Err(e) => {
+                    db_search
+                        .set_status(format!("Invalid search expression: {}: {}", expr, e).as_str());
+                    return;
+                }
+            };
+            for timestamp in timestamps {
                 if let Err(e) = db_search.search_at(&expr, timestamp) {
                     db_search
                         .set_status(format!("Invalid search expression: {}: {}", expr, e).as_str());
@@ -117,4 +126,30 @@ impl AsyncSignalDB {
             let _ = db_search.finish_search(&expr);
         }))
     }
+
+    /// Run an analysis (see [`analysis::run_command`]) in a separate thread. The findings are
+    /// registered in the `SignalDB` using the whole command line as search expression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::AsyncSignalDB;
+    /// let mut db = AsyncSignalDB::new();
+    /// db.analyze("glitch 10");
+    /// ```
+    ///
+    /// [`analysis::run_command`]: ../analysis/fn.run_command.html
+    pub fn analyze(&mut self, command_line: &str) {
+        let db_analysis = Arc::clone(&self.sync_db);
+        let command_line = command_line.to_string();
+        db_analysis.insert_findings(&command_line, Vec::new());
+        self.workers.push(thread::spawn(move || {
+            let (command, args) = command_line.split_once(' ').unwrap_or((&command_line, ""));
+            match analysis::run_command(&db_analysis, command, args) {
+                Ok(findings) => db_analysis.insert_findings(&command_line, findings),
+                Err(e) => db_analysis
+                    .set_status(format!("Analysis failed: {}: {}", command_line, e).as_str()),
+            }
+        }))
+    }
 }
@@ -1,14 +1,15 @@
 // SPDX-License-Identifier: MIT
 use super::scope::{Scope, ScopeChild};
 use super::signal::Signal;
-use super::time::{Scale, Timestamp};
+use super::time::{Scale, TimeDescr, Timestamp};
 use super::value::SignalValue;
 use crate::search::{FindingsSummary, Search};
-use crate::vcd::parser::Parser;
+use crate::vcd::parser::{Limit, Parser};
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::error::Error;
 use std::fmt;
 use std::io;
+use std::ops::Bound;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Condvar, Mutex};
 
@@ -50,7 +51,7 @@ impl fmt::Display for SignalNotFound {
 }
 
 impl SignalNotFound {
-    fn new(signal_id: &str) -> Self {
+    pub(crate) fn new(signal_id: &str) -> Self {
         SignalNotFound {
             signal_id: signal_id.to_string(),
         }
@@ -217,6 +218,39 @@ impl SignalDB {
         Ok(db)
     }
 
+    /// Create a `SignalDB` from a Value Capture Dump (VCD) file, ignoring the events after a
+    /// given time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, SignalDB, SignalValue, Timestamp};
+    /// let buf = std::io::Cursor::new("$timescale 10ns $end
+    /// $scope module top $end
+    /// $var wire 1 0 foo $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// #10
+    /// 10
+    /// #20
+    /// 00
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd_until(buf, "0.15us".parse().unwrap()).unwrap();
+    /// let timestamp = Timestamp::new(1, Scale::Microsecond);
+    /// assert_eq!(db.value_at("0", timestamp).unwrap(), SignalValue::new(1));
+    /// ```
+    pub fn from_vcd_until<I: io::BufRead>(
+        input: I,
+        timestamp: Timestamp,
+    ) -> Result<SignalDB, Box<dyn Error>> {
+        let db = SignalDB::new();
+        db.parse_vcd_until_limit(input, Some(Limit::Time(timestamp)))?;
+        Ok(db)
+    }
+
     /// Extend the current `SignalDB` with the signals defined in a Value Capture Dump (VCD) file.
     ///
     /// # Example
@@ -292,10 +326,18 @@ impl SignalDB {
         &self,
         input: I,
         timestamp: Option<i64>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.parse_vcd_until_limit(input, timestamp.map(Limit::Ticks))
+    }
+
+    fn parse_vcd_until_limit<I: io::BufRead>(
+        &self,
+        input: I,
+        limit: Option<Limit>,
     ) -> Result<(), Box<dyn Error>> {
         let mut parser = Parser::new(input, self);
-        if let Some(t) = timestamp {
-            parser.set_limit(t)
+        if let Some(limit) = limit {
+            parser.set_limit(limit)
         }
         self.set_status("Parsing VCD file...");
         parser.parse().map_err(|err| {
@@ -508,6 +550,21 @@ impl SignalDB {
         *now = timestamp;
     }
 
+    /// Get the current time of the `SignalDB`, i.e. the last timestamp reported so far.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, SignalDB, Timestamp};
+    /// let mut db = SignalDB::new();
+    /// db.set_time(Timestamp::new(42, Scale::Picosecond));
+    /// assert_eq!(db.get_time(), Timestamp::new(42, Scale::Picosecond));
+    /// ```
+    pub fn get_time(&self) -> Timestamp {
+        let now = self.now.lock().unwrap();
+        *now
+    }
+
     /// Set the value of a signal at the current time.
     ///
     /// # Example
@@ -566,6 +623,110 @@ impl SignalDB {
         timestamps.iter().cloned().collect()
     }
 
+    /// Return the latest timestamp where an event has been reported, which differs from the
+    /// current time if the timestamps of the VCD file are not monotonic.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, SignalDB, Timestamp};
+    /// let db = SignalDB::new();
+    /// db.set_time(Timestamp::new(42, Scale::Picosecond));
+    /// db.set_time(Timestamp::new(12, Scale::Picosecond));
+    /// assert_eq!(db.get_last_timestamp(), Timestamp::new(42, Scale::Picosecond));
+    /// ```
+    pub fn get_last_timestamp(&self) -> Timestamp {
+        let timestamps = self.timestamps.lock().unwrap();
+        timestamps.last().cloned().unwrap_or_else(Timestamp::origin)
+    }
+
+    /// Return the first `count` timestamps where an event has been reported, from a given time
+    /// included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, Signal, SignalDB, SignalValue, Timestamp};
+    /// let db = SignalDB::new();
+    /// db.create_scope(&vec!["top"]);
+    /// db.declare_signal(&vec!["top"], Signal::new("0", "foo", 8));
+    /// db.insert_event("0", Timestamp::new(42, Scale::Picosecond), SignalValue::new(1));
+    /// db.insert_event("0", Timestamp::new(43, Scale::Picosecond), SignalValue::new(2));
+    ///
+    /// let t = |v| Timestamp::new(v, Scale::Picosecond);
+    /// assert_eq!(db.get_timestamps_from(t(1), 2), vec![t(42), t(43)]);
+    /// assert_eq!(db.get_timestamps_from(t(43), 2), vec![t(43)]);
+    /// ```
+    pub fn get_timestamps_from(&self, timestamp: Timestamp, count: usize) -> Vec<Timestamp> {
+        let timestamps = self.timestamps.lock().unwrap();
+        timestamps.range(timestamp..).take(count).cloned().collect()
+    }
+
+    /// Return the timestamps, from `begin` included to `end` excluded, where one of several
+    /// signals changes, along with the timestamps reported right after each change. Neither the
+    /// values of the signals nor their previous values change between two of them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, SignalDB, Timestamp};
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 clk $end
+    /// $var wire 1 1 valid $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// 01
+    /// #10
+    /// 10
+    /// #20
+    /// 00
+    /// 11
+    /// #30
+    /// 10
+    /// #40
+    /// 00
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let t = |v| Timestamp::new(v, Scale::Picosecond);
+    /// assert_eq!(db.change_points(&["1"], t(0), t(40)).unwrap(), vec![t(0), t(10), t(20), t(30)]);
+    /// assert_eq!(db.change_points(&["1"], t(5), t(25)).unwrap(), vec![t(20)]);
+    /// assert!(db.change_points(&["2"], t(0), t(40)).is_err());
+    /// ```
+    pub fn change_points<S: AsRef<str>>(
+        &self,
+        signal_ids: &[S],
+        begin: Timestamp,
+        end: Timestamp,
+    ) -> Result<Vec<Timestamp>, SignalNotFound> {
+        let mut changes = BTreeSet::new();
+        {
+            let signals = self.signals.lock().unwrap();
+            for signal_id in signal_ids {
+                let signal_id = signal_id.as_ref();
+                let signal = signals
+                    .get(signal_id)
+                    .ok_or_else(|| SignalNotFound::new(signal_id))?;
+                changes.extend(signal.events_in(begin, end).map(|(timestamp, _)| timestamp))
+            }
+        }
+
+        let timestamps = self.timestamps.lock().unwrap();
+        let next_timestamps = changes
+            .iter()
+            .filter_map(|change| {
+                timestamps
+                    .range((Bound::Excluded(*change), Bound::Excluded(end)))
+                    .next()
+                    .cloned()
+            })
+            .collect::<Vec<_>>();
+        changes.extend(next_timestamps);
+        Ok(changes.into_iter().collect())
+    }
+
     /// Return value of a signal at a given time.
     ///
     /// # Example
@@ -606,6 +767,37 @@ impl SignalDB {
             .value_at(timestamp))
     }
 
+    /// Return the value held by a signal before its `n` last events reported up to a given time
+    /// included, if it was reported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, Signal, SignalDB, SignalValue, Timestamp};
+    /// let db = SignalDB::new();
+    /// db.create_scope(&vec!["top"]);
+    /// db.declare_signal(&vec!["top"], Signal::new("0", "foo", 8));
+    /// db.insert_event("0", Timestamp::new(10, Scale::Picosecond), SignalValue::new(1));
+    /// db.insert_event("0", Timestamp::new(20, Scale::Picosecond), SignalValue::new(2));
+    ///
+    /// let timestamp = Timestamp::new(25, Scale::Picosecond);
+    /// assert_eq!(db.past_value_at("0", timestamp, 1).unwrap(), Some(SignalValue::new(1)));
+    /// assert_eq!(db.past_value_at("0", timestamp, 2).unwrap(), None);
+    /// assert!(db.past_value_at("1", timestamp, 1).is_err());
+    /// ```
+    pub fn past_value_at(
+        &self,
+        signal_id: &str,
+        timestamp: Timestamp,
+        n: usize,
+    ) -> Result<Option<SignalValue>, SignalNotFound> {
+        let signals = self.signals.lock().unwrap();
+        Ok(signals
+            .get(signal_id)
+            .ok_or_else(|| SignalNotFound::new(signal_id))?
+            .past_value_at(timestamp, n))
+    }
+
     /// Return event of a signal reported at a given time.
     ///
     /// # Example
@@ -646,6 +838,69 @@ impl SignalDB {
             .event_at(timestamp))
     }
 
+    /// Iterate over the events of several signals reported within a time period, from `begin`
+    /// included to `end` excluded, as a single chronological stream of `(timestamp, signal_id,
+    /// value)`. Simultaneous events are given in the order of `signal_ids`. The events are copied
+    /// while locking the database only once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, SignalDB, SignalValue, Timestamp};
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 clk $end
+    /// $var wire 8 1 data $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// b0 1
+    /// #10
+    /// 10
+    /// b1 1
+    /// #15
+    /// 00
+    /// #20
+    /// 10
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let t = |v| Timestamp::new(v, Scale::Picosecond);
+    /// let events = db.iter_events(&["1", "0"], t(10), t(20)).unwrap().collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     events,
+    ///     vec![
+    ///         (t(10), "1".to_string(), SignalValue::new(1)),
+    ///         (t(10), "0".to_string(), SignalValue::new(1)),
+    ///         (t(15), "0".to_string(), SignalValue::new(0)),
+    ///     ]
+    /// );
+    /// assert!(db.iter_events(&["2"], t(0), t(20)).is_err());
+    /// ```
+    pub fn iter_events<S: AsRef<str>>(
+        &self,
+        signal_ids: &[S],
+        begin: Timestamp,
+        end: Timestamp,
+    ) -> Result<std::vec::IntoIter<(Timestamp, String, SignalValue)>, SignalNotFound> {
+        let signals = self.signals.lock().unwrap();
+        let mut events = Vec::new();
+        for signal_id in signal_ids {
+            let signal_id = signal_id.as_ref();
+            let signal = signals
+                .get(signal_id)
+                .ok_or_else(|| SignalNotFound::new(signal_id))?;
+            events.extend(
+                signal
+                    .events_in(begin, end)
+                    .map(|(timestamp, value)| (timestamp, signal_id.to_string(), value.clone())),
+            )
+        }
+        // The sort is stable, so simultaneous events remain in the order of the identifiers.
+        events.sort_by_key(|(timestamp, _, _)| *timestamp);
+        Ok(events.into_iter())
+    }
+
     /// Get fullname of a signal.
     ///
     /// # Example
@@ -723,6 +978,15 @@ impl SignalDB {
         matches
     }
 
+    /// Give access to the signals of the `SignalDB` while holding the lock only once.
+    pub(crate) fn with_signals<T, F>(&self, f: F) -> T
+    where
+        F: FnOnce(&BTreeMap<String, Signal>) -> T,
+    {
+        let signals = self.signals.lock().unwrap();
+        f(&signals)
+    }
+
     /// Return a vector of all the signal IDs registered in the `SignalDB`.
     ///
     /// # Example
@@ -853,6 +1117,45 @@ impl SignalDB {
             .get_next_falling_edge(timestamp))
     }
 
+    /// Count the rising edges of a signal reported within a time period, from `begin` included
+    /// to `end` excluded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, SignalDB, Timestamp};
+    /// let buf = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 clk $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// #10
+    /// 10
+    /// #15
+    /// 00
+    /// #20
+    /// 10
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(buf).unwrap();
+    /// let t = |v| Timestamp::new(v, Scale::Picosecond);
+    /// assert_eq!(db.count_rising_edges("0", t(0), t(20)).unwrap(), 1);
+    /// assert_eq!(db.count_rising_edges("0", t(0), t(21)).unwrap(), 2);
+    /// ```
+    pub fn count_rising_edges(
+        &self,
+        signal_id: &str,
+        begin: Timestamp,
+        end: Timestamp,
+    ) -> Result<usize, SignalNotFound> {
+        let signals = self.signals.lock().unwrap();
+        Ok(signals
+            .get(signal_id)
+            .ok_or_else(|| SignalNotFound::new(signal_id))?
+            .count_rising_edges(begin, end))
+    }
+
     /// Get the timestamp of the first event of the signal.
     ///
     /// # Example
@@ -1009,6 +1312,48 @@ impl SignalDB {
         Ok(())
     }
 
+    /// Search in the database and return the findings formatted by `search_all`. The search stops
+    /// as soon as `limit` findings are found, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, SignalDB, TimeDescr, Timestamp};
+    /// let vcd = std::io::Cursor::new("$scope module top $end
+    /// $var wire 1 0 foo $end
+    /// $upscope $end
+    /// $enddefinitions $end
+    /// #0
+    /// 00
+    /// #10
+    /// 10
+    /// #20
+    /// 00
+    /// #30
+    /// 10
+    /// #40
+    /// 00
+    /// ");
+    ///
+    /// let db = SignalDB::from_vcd(vcd).unwrap();
+    /// let t = |v| Timestamp::new(v, Scale::Picosecond);
+    /// assert_eq!(
+    ///     db.find_all("$0 = 1", None).unwrap(),
+    ///     vec![TimeDescr::Period(t(10), t(20)), TimeDescr::Period(t(30), t(40))]
+    /// );
+    /// assert_eq!(db.find_all("$0 <- 1", Some(1)).unwrap(), vec![TimeDescr::Point(t(10))]);
+    /// assert!(db.find_all("$0 =", None).is_err());
+    /// ```
+    pub fn find_all(
+        &self,
+        expr: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<TimeDescr>, Box<dyn Error>> {
+        let mut search = Search::new(expr)?;
+        search.search_first(self, limit.unwrap_or(usize::MAX))?;
+        Ok(search.findings().to_vec())
+    }
+
     /// Search in the `SignalDB`. The result of the search have to be retrieved with the functions
     /// defined below.
     ///
@@ -1080,6 +1425,25 @@ impl SignalDB {
         Ok(())
     }
 
+    /// Register findings computed outside of the search engine (e.g. by an analysis) so that they
+    /// can be retrieved with the functions defined below, using `name` as search expression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, SignalDB, TimeDescr, Timestamp};
+    /// let db = SignalDB::new();
+    /// let t = |v| Timestamp::new(v, Scale::Picosecond);
+    ///
+    /// db.insert_findings("foo", vec![TimeDescr::Period(t(42), t(50)), TimeDescr::Point(t(12))]);
+    /// assert_eq!(db.get_first_finding("foo").unwrap().unwrap(), t(12));
+    /// assert_eq!(db.get_last_finding("foo").unwrap().unwrap(), t(50));
+    /// ```
+    pub fn insert_findings(&self, name: &str, findings: Vec<TimeDescr>) {
+        let mut searches = self.searches.lock().unwrap();
+        searches.insert(name.to_string(), Search::from_findings(findings));
+    }
+
     /// Allocate a new search object in the `SignalDB` but don't perform actual search.
     /// This is meant to be used for asynchronous searches (see `AsyncSignalDB`)
     pub fn search_init(&self, expr: &str) -> Result<(), Box<dyn Error>> {
@@ -1089,6 +1453,18 @@ impl SignalDB {
         Ok(())
     }
 
+    /// Get the timestamps at which an expression has to be evaluated, i.e. the points of time at
+    /// which the signals it references change.
+    /// The expression must first be reported to the `SignalDB` using the `search_init` function.
+    /// This is meant to be used for asynchronous searches (see `AsyncSignalDB`)
+    pub fn search_timestamps(&self, expr: &str) -> Result<Vec<Timestamp>, Box<dyn Error>> {
+        let searches = self.searches.lock().unwrap();
+        searches
+            .get(expr)
+            .ok_or_else(|| SearchNotFound::new(expr))?
+            .all_timestamps(self)
+    }
+
     /// Check if an expression is valid at a given point of time.
     /// The expression must first be reported to the `SignalDB` using the `search_init` function.
     /// This is meant to be used for asynchronous searches (see `AsyncSignalDB`)
@@ -1279,10 +1655,32 @@ impl SignalDB {
     /// )
     /// ```
     pub fn format_values_at(&self, output: &mut dyn io::Write, timestamp: i64) {
+        let scale = self.timescale.lock().unwrap().scale;
+        self.format_values_at_time(output, Timestamp::new(timestamp, scale))
+    }
+
+    /// Format the value of the signals at a given timestamp.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, Signal, SignalDB, SignalValue, Timestamp};
+    /// let mut db = SignalDB::new();
+    ///
+    /// let scope = &vec!["foo"];
+    /// db.create_scope(&scope);
+    ///
+    /// let signal = Signal::new("0", "bar", 8);
+    /// db.declare_signal(&scope, signal);
+    /// db.insert_event("0", Timestamp::new(42, Scale::Nanosecond), SignalValue::new(0x2a));
+    ///
+    /// let mut buf = Vec::new();
+    /// db.format_values_at_time(&mut buf, "0.05us".parse().unwrap());
+    /// assert_eq!(String::from_utf8(buf).unwrap(), "foo\n  0 (bar) = h2A\n")
+    /// ```
+    pub fn format_values_at_time(&self, output: &mut dyn io::Write, timestamp: Timestamp) {
         let scope = self.scope.lock().unwrap();
         let signals = self.signals.lock().unwrap();
-        let timescale = self.timescale.lock().unwrap();
-        let timestamp = Timestamp::new(timestamp, timescale.scale);
         scope.traverse(&mut |name, node: &ScopeChild, depth| {
             for _ in 0..depth {
                 let _ = write!(output, "  ");
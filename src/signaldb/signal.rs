@@ -19,6 +19,7 @@ pub struct Signal {
     /// Width of the signal in bits
     pub width: usize,
     events: Vec<Event>,
+    collisions: Vec<Timestamp>,
     default: SignalValue,
     pub path: Vec<String>,
 }
@@ -41,6 +42,7 @@ impl Signal {
             name: name.to_string(),
             width,
             events: Vec::new(),
+            collisions: Vec::new(),
             default: SignalValue::new_default(width, BitValue::Undefined),
             path: Vec::new(),
         }
@@ -84,6 +86,11 @@ impl Signal {
 
         match seek {
             Ok(index) => {
+                if !self.events[index].new_value.is_invalid()
+                    && self.collisions.last() != Some(&timestamp)
+                {
+                    self.collisions.push(timestamp)
+                }
                 if *self.prev_value_at_index(index) == new_value {
                     let _ = self.events.remove(index);
                 } else {
@@ -104,6 +111,110 @@ impl Signal {
         }
     }
 
+    /// Get the timestamps where the `Signal` changed more than once.
+    ///
+    /// Overriding an unknown value (e.g. the initial `x` reported by `$dumpvars`) is not
+    /// considered as a collision.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, Signal, SignalValue, Timestamp};
+    /// let mut signal = Signal::new("0", "foo", 1);
+    /// signal.add_event(Timestamp::new(42, Scale::Picosecond), SignalValue::new(1));
+    /// signal.add_event(Timestamp::new(42, Scale::Picosecond), SignalValue::new(0));
+    /// assert_eq!(signal.get_collisions(), &[Timestamp::new(42, Scale::Picosecond)]);
+    /// ```
+    pub fn get_collisions(&self) -> &[Timestamp] {
+        &self.collisions
+    }
+
+    /// Iterate over the events of the `Signal`, in chronological order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, Signal, SignalValue, Timestamp};
+    /// let mut signal = Signal::new("0", "foo", 8);
+    /// for i in 0..4 {
+    ///     signal.add_event(Timestamp::new(i * 10, Scale::Picosecond), SignalValue::new(i as u64));
+    /// }
+    ///
+    /// let events = signal.events().collect::<Vec<_>>();
+    /// assert_eq!(events.len(), 4);
+    /// assert_eq!(events[1], (Timestamp::new(10, Scale::Picosecond), &SignalValue::new(1)));
+    ///
+    /// let events = signal
+    ///     .events_in(Timestamp::new(10, Scale::Picosecond), Timestamp::new(30, Scale::Picosecond))
+    ///     .map(|(t, _)| t.value)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(events, vec![10, 20]);
+    /// ```
+    pub fn events(&self) -> impl Iterator<Item = (Timestamp, &SignalValue)> {
+        self.events.iter().map(|e| (e.timestamp, &e.new_value))
+    }
+
+    /// Iterate over the events reported within a time period, from `begin` included to `end`
+    /// excluded.
+    ///
+    /// # Example
+    ///
+    /// See [`events`].
+    ///
+    /// [`events`]: #method.events
+    pub fn events_in(
+        &self,
+        begin: Timestamp,
+        end: Timestamp,
+    ) -> impl Iterator<Item = (Timestamp, &SignalValue)> {
+        let end_index = self.index_of(end);
+        let begin_index = self.index_of(begin).min(end_index);
+        self.events[begin_index..end_index]
+            .iter()
+            .map(|e| (e.timestamp, &e.new_value))
+    }
+
+    /// Iterate over the timestamps of the rising edges, i.e. the events setting the `Signal` to a
+    /// value other than zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, Signal, SignalValue, Timestamp};
+    /// let mut signal = Signal::new("0", "clk", 1);
+    /// for i in 0..6 {
+    ///     signal.add_event(Timestamp::new(i, Scale::Picosecond), SignalValue::new(i as u64 & 1));
+    /// }
+    ///
+    /// let rising = signal.rising_edges().map(|t| t.value).collect::<Vec<_>>();
+    /// assert_eq!(rising, vec![1, 3, 5]);
+    /// let falling = signal.falling_edges().map(|t| t.value).collect::<Vec<_>>();
+    /// assert_eq!(falling, vec![0, 2, 4]);
+    /// ```
+    pub fn rising_edges(&self) -> impl Iterator<Item = Timestamp> {
+        let zero = SignalValue::new(0);
+        self.events
+            .iter()
+            .filter(move |e| e.new_value != zero)
+            .map(|e| e.timestamp)
+    }
+
+    /// Iterate over the timestamps of the falling edges, i.e. the events setting the `Signal` to
+    /// zero.
+    ///
+    /// # Example
+    ///
+    /// See [`rising_edges`].
+    ///
+    /// [`rising_edges`]: #method.rising_edges
+    pub fn falling_edges(&self) -> impl Iterator<Item = Timestamp> {
+        let zero = SignalValue::new(0);
+        self.events
+            .iter()
+            .filter(move |e| e.new_value == zero)
+            .map(|e| e.timestamp)
+    }
+
     /// Get value of the `Signal` at a given time.
     ///
     /// # Example
@@ -150,6 +261,42 @@ impl Signal {
         }
     }
 
+    /// Get the value held by the `Signal` before its `n` last events reported up to a given time
+    /// included, if it was reported. The value at that time is returned if `n` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, Signal, SignalValue, Timestamp};
+    /// let mut signal = Signal::new("0", "foo", 8);
+    /// signal.add_event(Timestamp::new(10, Scale::Picosecond), SignalValue::new(1));
+    /// signal.add_event(Timestamp::new(20, Scale::Picosecond), SignalValue::new(2));
+    /// signal.add_event(Timestamp::new(30, Scale::Picosecond), SignalValue::new(3));
+    ///
+    /// let timestamp = Timestamp::new(25, Scale::Picosecond);
+    /// assert_eq!(signal.past_value_at(timestamp, 0), Some(SignalValue::new(2)));
+    /// assert_eq!(signal.past_value_at(timestamp, 1), Some(SignalValue::new(1)));
+    /// assert_eq!(
+    ///     signal.past_value_at(Timestamp::new(30, Scale::Picosecond), 1),
+    ///     Some(SignalValue::new(2))
+    /// );
+    /// assert_eq!(signal.past_value_at(timestamp, 2), None);
+    /// ```
+    pub fn past_value_at(&self, timestamp: Timestamp, n: usize) -> Option<SignalValue> {
+        let seek = self
+            .events
+            .binary_search_by_key(&timestamp, |e| e.timestamp);
+
+        let index = match seek {
+            Ok(index) => index + 1,
+            Err(index) => index,
+        };
+        if n > 0 && index <= n {
+            return None;
+        }
+        Some(self.prev_value_at_index(index - n).clone())
+    }
+
     fn index_of(&self, timestamp: Timestamp) -> usize {
         let seek = self
             .events
@@ -279,6 +426,69 @@ impl Signal {
         None
     }
 
+    /// Count the rising edges reported within a time period.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, Signal, SignalValue, Timestamp};
+    /// let mut signal = Signal::new("0", "clk", 1);
+    /// for i in 0..10 {
+    ///     signal.add_event(Timestamp::new(i, Scale::Picosecond), SignalValue::new(i as u64 & 1));
+    /// }
+    ///
+    /// assert_eq!(
+    ///     signal.count_rising_edges(
+    ///         Timestamp::new(1, Scale::Picosecond), Timestamp::new(5, Scale::Picosecond)
+    ///     ),
+    ///     2
+    /// );
+    /// ```
+    pub fn count_rising_edges(&self, begin: Timestamp, end: Timestamp) -> usize {
+        let zero = SignalValue::new(0);
+        self.events[self.index_of(begin)..self.index_of(end)]
+            .iter()
+            .filter(|evt| evt.new_value != zero)
+            .count()
+    }
+
+    /// Count the `0 -> 1` and `1 -> 0` transitions of each bit of the `Signal` for a time period.
+    /// The counters are indexed from the least significant bit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, Signal, SignalValue, Timestamp};
+    /// let mut signal = Signal::new("0", "foo", 2);
+    /// signal.add_event(Timestamp::new(0, Scale::Picosecond), SignalValue::new(0));
+    /// signal.add_event(Timestamp::new(1, Scale::Picosecond), SignalValue::new(1));
+    /// signal.add_event(Timestamp::new(2, Scale::Picosecond), SignalValue::new(2));
+    ///
+    /// assert_eq!(
+    ///     signal.count_bit_toggles(
+    ///         Timestamp::new(0, Scale::Picosecond), Timestamp::new(5, Scale::Picosecond)
+    ///     ),
+    ///     vec![(1, 1), (1, 0)]
+    /// );
+    /// ```
+    pub fn count_bit_toggles(&self, begin: Timestamp, end: Timestamp) -> Vec<(usize, usize)> {
+        let begin_index = self.index_of(begin);
+        let mut toggles = vec![(0, 0); self.width];
+        let mut prev = self.prev_value_at_index(begin_index);
+
+        for evt in &self.events[begin_index..self.index_of(end)] {
+            for (i, (rises, falls)) in toggles.iter_mut().enumerate() {
+                match (prev.get_bit(i), evt.new_value.get_bit(i)) {
+                    (BitValue::Low, BitValue::High) => *rises += 1,
+                    (BitValue::High, BitValue::Low) => *falls += 1,
+                    _ => {}
+                }
+            }
+            prev = &evt.new_value
+        }
+        toggles
+    }
+
     /// Get the timestamp of the first event.
     ///
     /// # Example
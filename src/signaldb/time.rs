@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: MIT
 use std::cmp::Ordering;
+use std::error::Error;
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
 use std::str::FromStr;
 use std::{convert, fmt};
@@ -25,7 +26,7 @@ pub struct Timestamp {
 }
 
 /// Description of a time period
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum TimeDescr {
     /// Representation of a point in time
     Point(Timestamp),
@@ -33,6 +34,36 @@ pub enum TimeDescr {
     Period(Timestamp, Timestamp),
 }
 
+/// Error returned when a time literal cannot be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidTimestamp {
+    literal: String,
+}
+
+impl InvalidTimestamp {
+    fn new(literal: &str) -> Self {
+        InvalidTimestamp {
+            literal: literal.to_string(),
+        }
+    }
+}
+
+impl Error for InvalidTimestamp {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for InvalidTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid time '{}' (expected a number followed by s, ms, us, ns, ps or fs)",
+            self.literal
+        )
+    }
+}
+
 impl Scale {
     fn scale_down(self) -> Option<Scale> {
         match self {
@@ -355,6 +386,57 @@ impl PartialEq for Timestamp {
     }
 }
 
+impl FromStr for Timestamp {
+    type Err = InvalidTimestamp;
+
+    /// Parse a time literal made of a number and a unit. Fractional values are expressed in a
+    /// finer scale.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{Scale, Timestamp};
+    /// let t: Timestamp = "10ns".parse().unwrap();
+    /// assert_eq!(t, Timestamp::new(10, Scale::Nanosecond));
+    ///
+    /// let t: Timestamp = "1.5us".parse().unwrap();
+    /// assert_eq!(t, Timestamp::new(1500, Scale::Nanosecond));
+    /// assert_eq!(t.scale, Scale::Nanosecond);
+    ///
+    /// let t: Timestamp = "250 ps".parse().unwrap();
+    /// assert_eq!(t, Timestamp::new(250, Scale::Picosecond));
+    ///
+    /// assert!("42".parse::<Timestamp>().is_err());
+    /// assert!("1.5fs".parse::<Timestamp>().is_err());
+    /// assert!("10 parsecs".parse::<Timestamp>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || InvalidTimestamp::new(s);
+        let literal = s.trim();
+        let split = literal
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .ok_or_else(err)?;
+        let (number, unit) = literal.split_at(split);
+        let mut scale: Scale = unit.trim_start().parse().map_err(|_| err())?;
+
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let mut value: i64 = integer.parse().map_err(|_| err())?;
+        let mut fraction = fraction.trim_end_matches('0');
+        while !fraction.is_empty() {
+            scale = scale.scale_down().ok_or_else(err)?;
+            let (digits, rest) = fraction.split_at(fraction.len().min(3));
+            let digits: i64 = format!("{:0<3}", digits).parse().map_err(|_| err())?;
+            value = value
+                .checked_mul(1000)
+                .and_then(|value| value.checked_add(digits))
+                .ok_or_else(err)?;
+            fraction = rest;
+        }
+
+        Ok(Timestamp::new(value, scale))
+    }
+}
+
 impl fmt::Display for Timestamp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}{}", self.value, self.scale)
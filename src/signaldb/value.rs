@@ -4,7 +4,7 @@ use std::fmt;
 use std::str::FromStr;
 
 /// Value a single bit
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum BitValue {
     Low,
     High,
@@ -247,6 +247,52 @@ impl SignalValue {
         }
     }
 
+    /// Get the value of a bit, the least significant bit having the index 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{BitValue, SignalValue};
+    /// let v = SignalValue::new(2);
+    /// assert_eq!(v.get_bit(0), BitValue::Low);
+    /// assert_eq!(v.get_bit(1), BitValue::High);
+    /// assert_eq!(v.get_bit(42), BitValue::Low);
+    /// ```
+    pub fn get_bit(&self, index: usize) -> BitValue {
+        match self {
+            SignalValue::Literal(literal, _) => *literal.get(index).unwrap_or(&BitValue::Low),
+            SignalValue::Symbol(_) => BitValue::Invalid,
+        }
+    }
+
+    /// Convert the `SignalValue` to an integer. Returns `None` if the value is not fully known
+    /// or does not fit in 64 bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::SignalValue;
+    /// assert_eq!(SignalValue::new(42).to_u64(), Some(42));
+    /// assert_eq!(SignalValue::invalid().to_u64(), None);
+    /// assert_eq!(SignalValue::from_symbol_str("foo").to_u64(), None);
+    /// ```
+    pub fn to_u64(&self) -> Option<u64> {
+        match self {
+            SignalValue::Literal(literal, _) => {
+                let mut value = 0;
+                for (i, b) in literal.iter().enumerate() {
+                    match (b, i) {
+                        (BitValue::Low, _) => {}
+                        (BitValue::High, 0..64) => value |= 1 << i,
+                        _ => return None,
+                    }
+                }
+                Some(value)
+            }
+            SignalValue::Symbol(_) => None,
+        }
+    }
+
     /// Check if the `SignalValue` is invalid.
     ///
     /// # Example
@@ -272,6 +318,47 @@ impl SignalValue {
             SignalValue::Symbol(_) => false,
         }
     }
+
+    /// Check if one of the bits of the `SignalValue` has a given value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::{BitValue, SignalValue};
+    /// let v: SignalValue = "10z1".parse().unwrap();
+    /// assert!(v.has_bit(BitValue::HighZ));
+    /// assert!(!v.has_bit(BitValue::Undefined));
+    /// ```
+    pub fn has_bit(&self, bit: BitValue) -> bool {
+        match self {
+            SignalValue::Literal(literal, _) => literal.contains(&bit),
+            SignalValue::Symbol(_) => false,
+        }
+    }
+
+    /// Compare two `SignalValue`s, ignoring the bits set in a mask of don't-care bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dwfv::signaldb::SignalValue;
+    /// let pattern = SignalValue::new(0b1000);
+    /// let ignored = SignalValue::new(0b0110);
+    /// assert!(SignalValue::new(0b1010).eq_ignoring(&pattern, &ignored));
+    /// assert!(SignalValue::new(0b1110).eq_ignoring(&pattern, &ignored));
+    /// assert!(!SignalValue::new(0b1001).eq_ignoring(&pattern, &ignored));
+    /// assert!(!SignalValue::new(0b11000).eq_ignoring(&pattern, &ignored));
+    /// ```
+    pub fn eq_ignoring(&self, other: &SignalValue, ignored: &SignalValue) -> bool {
+        match (self, other) {
+            (SignalValue::Literal(self_l, _), SignalValue::Literal(other_l, _)) => {
+                (0..self_l.len().max(other_l.len())).all(|i| {
+                    ignored.get_bit(i) == BitValue::High || self.get_bit(i) == other.get_bit(i)
+                })
+            }
+            _ => self == other,
+        }
+    }
 }
 
 impl fmt::Display for SignalValue {
@@ -4,6 +4,7 @@ mod cursorbar;
 mod errorbar;
 mod event;
 pub mod instr;
+mod memorypane;
 mod searchbar;
 mod statusbar;
 mod symbols;
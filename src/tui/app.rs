@@ -3,11 +3,15 @@ use super::cursorbar::{CursorBar, CursorType};
 use super::errorbar::ErrorBar;
 use super::event::{Event, Events, SearchTarget};
 use super::instr::TuiInstr;
+use super::memorypane::MemoryPane;
 use super::searchbar::SearchBar;
 use super::statusbar::StatusBar;
 use super::waveform::{Waveform, WaveformElement};
+use crate::analysis::{self, Memory, WORDS_PER_LINE, WritePort, Xlen};
+use crate::search::{FindingsSummary, ParseError};
 use crate::signaldb::{AsyncSignalDB, Scale, SignalValue, Timestamp};
 use std::cmp::{self, Ordering};
+use std::collections::HashMap;
 use std::env;
 use std::error::Error;
 use std::fs::File;
@@ -22,7 +26,7 @@ use tui::terminal::Frame;
 
 const MAX_ID_SIZE: usize = 28;
 const MAX_SCALE_VALUE: i64 = 1 << 16;
-const HELP_MSG: &str = "q:Quit  h,j,k,l:Move  +,-,=:Zoom  v:Select  /,f:Search  o:Edit  \
+const HELP_MSG: &str = "q:Quit  h,j,k,l:Move  +,-,=:Zoom  v:Select  /,f:Search  t:Goto  o:Edit  \
     yy:Peek  p,P:Pop  dd:Stash  u,r:Undo/Redo";
 
 #[derive(Clone)]
@@ -49,6 +53,7 @@ pub struct App {
     memento: Memento,
     clipboard: Vec<TuiInstr>,
     search_pattern: String,
+    memories: HashMap<String, (Timestamp, Memory)>,
 }
 
 impl App {
@@ -85,6 +90,7 @@ impl App {
             },
             clipboard: Vec::new(),
             search_pattern: String::new(),
+            memories: HashMap::new(),
         };
 
         app.goto_first_event();
@@ -146,9 +152,14 @@ impl App {
         f: &mut Frame<B>,
         rect: Rect,
         signal_id: &str,
+        translation: Option<Xlen>,
         selected: bool,
         odd: bool,
     ) -> Result<(), Box<dyn Error>> {
+        let display = |value: &SignalValue| match translation {
+            Some(xlen) => xlen.translate(value),
+            None => format!("{}", value),
+        };
         let mut data = Vec::new();
         for i in 0..rect.width {
             let (begin, end) = self.get_time_range(i);
@@ -166,7 +177,7 @@ impl App {
                         data.push(WaveformElement::High)
                     }
                 } else {
-                    data.push(WaveformElement::Value(format!("{}", before)))
+                    data.push(WaveformElement::Value(display(&before)))
                 }
             } else if nb_events == 1 {
                 if before.width() == 1 {
@@ -198,7 +209,7 @@ impl App {
                 } else {
                     fullname
                 },
-                value
+                display(&value)
             ),
             &data[..],
             selected,
@@ -233,6 +244,73 @@ impl App {
         Ok(())
     }
 
+    fn render_invalid_search<B: Backend>(
+        &mut self,
+        f: &mut Frame<B>,
+        rect: Rect,
+        err: &ParseError,
+        selected: bool,
+    ) {
+        let data = vec![FindingsSummary::Nothing; rect.width as usize];
+        let search_bar = SearchBar::new(
+            format!("{}{}", if selected { "> " } else { "  " }, err.expr()),
+            &data[..],
+            selected,
+            self.get_relative_cursor_x(),
+            self.get_relative_visual_cursor_x(),
+        )
+        .underline(err.column() + 1, err.token().chars().count().max(1));
+        f.render_widget(search_bar, rect);
+    }
+
+    fn render_memory<B: Backend>(
+        &mut self,
+        f: &mut Frame<B>,
+        rect: Rect,
+        port: &str,
+        selected: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        // The memory is reconstructed again whenever more of the dump has been parsed.
+        let time = self.signaldb.sync_db.get_time();
+        if self.memories.get(port).is_none_or(|(t, _)| *t != time) {
+            let spec = port.split_whitespace().collect::<Vec<_>>().join(",");
+            let memory = Memory::reconstruct(&self.signaldb.sync_db, &WritePort::parse(&spec)?)?;
+            self.memories.insert(port.to_string(), (time, memory));
+        }
+        let memory = &self.memories[port].1;
+
+        let lines = memory.dump(self.cursor.x);
+        let last_write = memory
+            .writes()
+            .iter()
+            .rev()
+            .find(|w| w.timestamp <= self.cursor.x);
+        let digits = memory.format_word(None).len();
+        let highlight = last_write.and_then(|w| {
+            let line = lines
+                .iter()
+                .position(|(base, _)| *base == w.address - w.address % WORDS_PER_LINE)?;
+            Some((
+                line,
+                (w.address % WORDS_PER_LINE) as usize * (digits + 1),
+                digits,
+            ))
+        });
+        let title = match last_write {
+            Some(w) => format!(
+                "{}memory {}: last write {:x} <- {} at {}",
+                if selected { "> " } else { "  " },
+                port,
+                w.address,
+                w.data,
+                w.timestamp
+            ),
+            None => format!("{}memory {}", if selected { "> " } else { "  " }, port),
+        };
+        f.render_widget(MemoryPane::new(title, &lines, highlight, selected), rect);
+        Ok(())
+    }
+
     fn render_error<B: Backend>(
         &mut self,
         f: &mut Frame<B>,
@@ -253,8 +331,14 @@ impl App {
         odd: bool,
     ) -> Result<(), Box<dyn Error>> {
         match instr {
-            TuiInstr::Signal(id) => self.render_waveform(f, rect, id, selected, odd)?,
-            TuiInstr::Search(expr) => self.render_search(f, rect, expr, selected)?,
+            TuiInstr::Signal(id) => self.render_waveform(f, rect, id, None, selected, odd)?,
+            TuiInstr::Disassembly(id, xlen) => {
+                self.render_waveform(f, rect, id, Some(*xlen), selected, odd)?
+            }
+            TuiInstr::Search(expr, Ok(_)) => self.render_search(f, rect, expr, selected)?,
+            TuiInstr::Search(_, Err(err)) => self.render_invalid_search(f, rect, err, selected),
+            TuiInstr::Analysis(expr) => self.render_search(f, rect, expr, selected)?,
+            TuiInstr::Memory(port) => self.render_memory(f, rect, port, selected)?,
             TuiInstr::Error(line, err) => {
                 self.render_error(f, rect, format!("{}: {}", line, err), selected)
             }
@@ -286,7 +370,7 @@ impl App {
         let last_instr = Rect::new(self.area.x, self.height + 1, self.area.width, 1);
         let default_signal_name = String::new();
         let signal_name = match &self.layout[self.cursor.y] {
-            TuiInstr::Signal(id) => self
+            TuiInstr::Signal(id) | TuiInstr::Disassembly(id, _) => self
                 .signaldb
                 .sync_db
                 .get_signal_fullname(id)
@@ -490,10 +574,14 @@ impl App {
         let mut reviewed_layout = Vec::new();
         for instr in layout.iter() {
             match instr {
-                TuiInstr::Search(expr) => {
+                TuiInstr::Search(expr, _) => {
                     self.signaldb.search(expr);
                     reviewed_layout.push(instr.clone())
                 }
+                TuiInstr::Analysis(command_line) => {
+                    self.signaldb.analyze(command_line);
+                    reviewed_layout.push(instr.clone())
+                }
                 TuiInstr::Signal(signal) => {
                     if self.signaldb.sync_db.signal_exists(signal) {
                         reviewed_layout.push(TuiInstr::Signal(signal.clone()))
@@ -509,7 +597,24 @@ impl App {
                         }
                     }
                 }
-                TuiInstr::Error(_, _) => reviewed_layout.push(instr.clone()),
+                TuiInstr::Disassembly(signal, xlen) => {
+                    let id = if self.signaldb.sync_db.signal_exists(signal) {
+                        Some(signal.clone())
+                    } else {
+                        self.signaldb
+                            .sync_db
+                            .find_signals(|s| s.name == *signal)
+                            .pop()
+                    };
+                    match id {
+                        Some(id) => reviewed_layout.push(TuiInstr::Disassembly(id, *xlen)),
+                        None => reviewed_layout.push(TuiInstr::Error(
+                            instr.to_string(),
+                            "Unknown signal".to_string(),
+                        )),
+                    }
+                }
+                TuiInstr::Memory(_) | TuiInstr::Error(_, _) => reviewed_layout.push(instr.clone()),
             }
         }
         self.layout = reviewed_layout
@@ -517,16 +622,16 @@ impl App {
 
     fn goto_next_rising_edge(&mut self) {
         let res = match &self.layout[self.cursor.y] {
-            TuiInstr::Signal(id) => self
+            TuiInstr::Signal(id) | TuiInstr::Disassembly(id, _) => self
                 .signaldb
                 .sync_db
                 .get_next_rising_edge(id, self.cursor.x)
                 .unwrap(),
-            TuiInstr::Search(expr) => self
+            TuiInstr::Search(expr, _) | TuiInstr::Analysis(expr) => self
                 .signaldb
                 .sync_db
                 .get_next_finding(expr, self.cursor.x)
-                .unwrap(),
+                .unwrap_or(None),
             _ => None,
         };
         if let Some(t) = res {
@@ -539,16 +644,16 @@ impl App {
 
     fn goto_next_falling_edge(&mut self) {
         let res = match &self.layout[self.cursor.y] {
-            TuiInstr::Signal(id) => self
+            TuiInstr::Signal(id) | TuiInstr::Disassembly(id, _) => self
                 .signaldb
                 .sync_db
                 .get_next_falling_edge(id, self.cursor.x)
                 .unwrap(),
-            TuiInstr::Search(expr) => self
+            TuiInstr::Search(expr, _) | TuiInstr::Analysis(expr) => self
                 .signaldb
                 .sync_db
                 .get_end_of_next_finding(expr, self.cursor.x)
-                .unwrap(),
+                .unwrap_or(None),
             _ => None,
         };
         if let Some(t) = res {
@@ -561,12 +666,12 @@ impl App {
 
     fn goto_previous_rising_edge(&mut self) {
         let res = match &self.layout[self.cursor.y] {
-            TuiInstr::Signal(id) => self
+            TuiInstr::Signal(id) | TuiInstr::Disassembly(id, _) => self
                 .signaldb
                 .sync_db
                 .get_previous_rising_edge(id, self.cursor.x)
                 .unwrap_or(None),
-            TuiInstr::Search(expr) => self
+            TuiInstr::Search(expr, _) | TuiInstr::Analysis(expr) => self
                 .signaldb
                 .sync_db
                 .get_previous_finding(expr, self.cursor.x)
@@ -583,8 +688,10 @@ impl App {
 
     fn goto_first_event(&mut self) {
         let res = match &self.layout[self.cursor.y] {
-            TuiInstr::Signal(id) => self.signaldb.sync_db.get_first_event(id).unwrap_or(None),
-            TuiInstr::Search(expr) => self
+            TuiInstr::Signal(id) | TuiInstr::Disassembly(id, _) => {
+                self.signaldb.sync_db.get_first_event(id).unwrap_or(None)
+            }
+            TuiInstr::Search(expr, _) | TuiInstr::Analysis(expr) => self
                 .signaldb
                 .sync_db
                 .get_first_finding(expr)
@@ -601,8 +708,12 @@ impl App {
 
     fn goto_last_event(&mut self) {
         let res = match &self.layout[self.cursor.y] {
-            TuiInstr::Signal(id) => self.signaldb.sync_db.get_last_event(id).unwrap_or(None),
-            TuiInstr::Search(expr) => self.signaldb.sync_db.get_last_finding(expr).unwrap_or(None),
+            TuiInstr::Signal(id) | TuiInstr::Disassembly(id, _) => {
+                self.signaldb.sync_db.get_last_event(id).unwrap_or(None)
+            }
+            TuiInstr::Search(expr, _) | TuiInstr::Analysis(expr) => {
+                self.signaldb.sync_db.get_last_finding(expr).unwrap_or(None)
+            }
             _ => None,
         };
         if let Some(t) = res {
@@ -627,11 +738,11 @@ impl App {
 
     fn zoom_fit(&mut self) {
         let period = match &self.layout[self.cursor.y] {
-            TuiInstr::Signal(id) => Some((
+            TuiInstr::Signal(id) | TuiInstr::Disassembly(id, _) => Some((
                 self.signaldb.sync_db.get_first_event(id).unwrap_or(None),
                 self.signaldb.sync_db.get_last_event(id).unwrap_or(None),
             )),
-            TuiInstr::Search(expr) => Some((
+            TuiInstr::Search(expr, _) | TuiInstr::Analysis(expr) => Some((
                 self.signaldb
                     .sync_db
                     .get_first_finding(expr)
@@ -661,8 +772,11 @@ impl App {
 
     fn matches_search_pattern(&self, instr: &TuiInstr) -> bool {
         let id = match instr {
-            TuiInstr::Signal(id) => self.signaldb.sync_db.get_signal_fullname(id).unwrap(),
-            TuiInstr::Search(expr) => expr.to_string(),
+            TuiInstr::Signal(id) | TuiInstr::Disassembly(id, _) => {
+                self.signaldb.sync_db.get_signal_fullname(id).unwrap()
+            }
+            TuiInstr::Search(expr, _) | TuiInstr::Analysis(expr) => expr.to_string(),
+            TuiInstr::Memory(_) => instr.to_string(),
             _ => return false,
         };
         id.contains(&self.search_pattern)
@@ -778,16 +892,25 @@ impl App {
                 self.search_next()
             }
             SearchTarget::Event => {
-                if let TuiInstr::Signal(signal_id) = &self.layout[self.cursor.y] {
+                if let TuiInstr::Signal(signal_id) | TuiInstr::Disassembly(signal_id, _) =
+                    &self.layout[self.cursor.y]
+                {
                     let expr = format!("${} = {}", signal_id, pattern);
                     self.signaldb.search(&expr);
-                    let instr = TuiInstr::Search(expr);
+                    let instr = TuiInstr::search(expr);
                     self.snapshot_layout();
                     self.layout.insert(self.cursor.y + 1, instr);
                 } else {
                     self.set_status("Cannot search events in this line")
                 }
             }
+            SearchTarget::Time => match analysis::parse_time(&self.signaldb.sync_db, pattern) {
+                Ok(timestamp) => {
+                    self.cursor.x = timestamp;
+                    self.center_window()
+                }
+                Err(err) => self.set_status(&err.to_string()),
+            },
             _ => {}
         }
     }
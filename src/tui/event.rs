@@ -12,6 +12,7 @@ pub enum SearchTarget {
     None,
     Signal,
     Event,
+    Time,
 }
 
 #[allow(clippy::enum_variant_names)]
@@ -102,7 +103,7 @@ impl Events {
         self.buffer.clear()
     }
 
-    const CMDS: [(&'static str, Command); 35] = [
+    const CMDS: [(&'static str, Command); 36] = [
         ("j", &|_| Event::Down),
         ("k", &|_| Event::Up),
         ("l", &|_| Event::Right),
@@ -161,6 +162,11 @@ impl Events {
             evt.buffer.clear();
             Event::None
         }),
+        ("t", &|evt| {
+            evt.mode = InputMode::Search(SearchTarget::Time);
+            evt.buffer.clear();
+            Event::None
+        }),
         (".", &|evt| {
             evt.buffer.clear();
             evt.buffer.push_str(&evt.previous_buffer);
@@ -293,10 +299,10 @@ impl Events {
                         } else {
                             self.buffer.push(c);
                             match self.mode {
-                                InputMode::Command | InputMode::Visual => {
-                                    if self.buffer.len() >= BUFFER_MAX_SIZE {
-                                        self.buffer.clear()
-                                    }
+                                InputMode::Command | InputMode::Visual
+                                    if self.buffer.len() >= BUFFER_MAX_SIZE =>
+                                {
+                                    self.buffer.clear()
                                 }
                                 _ => {}
                             }
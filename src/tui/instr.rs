@@ -1,4 +1,6 @@
 // SPDX-License-Identifier: MIT
+use crate::analysis::{self, Xlen};
+use crate::search::{ExprAst, ParseError};
 use std::fmt;
 use std::io;
 use std::io::prelude::*;
@@ -8,17 +10,31 @@ use std::io::prelude::*;
 pub enum TuiInstr {
     /// Tell the TUI to display a signal.
     Signal(String),
-    /// Tell the TUI to display the result of a search expression.
-    Search(String),
+    /// Tell the TUI to display a signal carrying RISC-V instructions.
+    Disassembly(String, Xlen),
+    /// Tell the TUI to display the result of a search expression, parsed once and for all.
+    Search(String, Result<ExprAst, ParseError>),
+    /// Tell the TUI to display the findings of an analysis.
+    Analysis(String),
+    /// Tell the TUI to display the content of a memory at the cursor.
+    Memory(String),
     /// Tell the TUI to display an error message.
     Error(String, String),
 }
 
 impl TuiInstr {
+    pub fn search(expr: String) -> TuiInstr {
+        let ast = expr.parse();
+        TuiInstr::Search(expr, ast)
+    }
+
     pub fn height(&self) -> usize {
         match self {
             TuiInstr::Signal(_) => 3,
-            TuiInstr::Search(_) => 1,
+            TuiInstr::Disassembly(_, _) => 3,
+            TuiInstr::Search(_, _) => 1,
+            TuiInstr::Analysis(_) => 1,
+            TuiInstr::Memory(_) => 8,
             TuiInstr::Error(_, _) => 1,
         }
     }
@@ -40,7 +56,17 @@ impl TuiInstr {
         let arg = v.last().unwrap().to_string();
         match *instr {
             "signal" => TuiInstr::Signal(arg),
-            "search" => TuiInstr::Search(arg),
+            "disasm" => match arg.split_whitespace().collect::<Vec<_>>()[..] {
+                [id] | [id, "rv32"] => TuiInstr::Disassembly(id.to_string(), Xlen::Rv32),
+                [id, "rv64"] => TuiInstr::Disassembly(id.to_string(), Xlen::Rv64),
+                _ => TuiInstr::Error(
+                    line.to_string(),
+                    "Expected 'disasm ID [rv32|rv64]'".to_string(),
+                ),
+            },
+            "search" => TuiInstr::search(arg),
+            "memory" => TuiInstr::Memory(arg),
+            cmd if analysis::is_command(cmd) => TuiInstr::Analysis(line.to_string()),
             _ => TuiInstr::Error(line.to_string(), format!("Unknown command '{}'", instr)),
         }
     }
@@ -72,7 +98,11 @@ impl fmt::Display for TuiInstr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             TuiInstr::Signal(s) => write!(f, "signal {}", s),
-            TuiInstr::Search(s) => write!(f, "search {}", s),
+            TuiInstr::Disassembly(s, Xlen::Rv32) => write!(f, "disasm {}", s),
+            TuiInstr::Disassembly(s, Xlen::Rv64) => write!(f, "disasm {} rv64", s),
+            TuiInstr::Search(s, _) => write!(f, "search {}", s),
+            TuiInstr::Analysis(s) => write!(f, "{}", s),
+            TuiInstr::Memory(s) => write!(f, "memory {}", s),
             TuiInstr::Error(s, _) => write!(f, "{}", s),
         }?;
         Ok(())
@@ -83,7 +113,10 @@ impl PartialEq for TuiInstr {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (TuiInstr::Signal(a), TuiInstr::Signal(b)) => a == b,
-            (TuiInstr::Search(a), TuiInstr::Search(b)) => a == b,
+            (TuiInstr::Disassembly(a, xa), TuiInstr::Disassembly(b, xb)) => a == b && xa == xb,
+            (TuiInstr::Search(a, _), TuiInstr::Search(b, _)) => a == b,
+            (TuiInstr::Analysis(a), TuiInstr::Analysis(b)) => a == b,
+            (TuiInstr::Memory(a), TuiInstr::Memory(b)) => a == b,
             (TuiInstr::Error(la, ma), TuiInstr::Error(lb, mb)) => la == lb && ma == mb,
             _ => false,
         }
@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: MIT
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::{Color, Style};
+use tui::widgets::Widget;
+
+pub struct MemoryPane<'a> {
+    name: String,
+    lines: &'a [(u64, String)],
+    highlight: Option<(usize, usize, usize)>,
+    selected: bool,
+}
+
+impl<'a> MemoryPane<'a> {
+    /// Create a pane displaying the lines of a hex dump. The highlighted word is described by its
+    /// line, its offset within the line and its length.
+    pub fn new(
+        name: String,
+        lines: &'a [(u64, String)],
+        highlight: Option<(usize, usize, usize)>,
+        selected: bool,
+    ) -> MemoryPane<'a> {
+        MemoryPane {
+            name,
+            lines,
+            highlight,
+            selected,
+        }
+    }
+}
+
+impl Widget for MemoryPane<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        buf.set_stringn(
+            area.left(),
+            area.top(),
+            &self.name,
+            area.width as usize,
+            Style::default()
+                .bg(if self.selected {
+                    Color::White
+                } else {
+                    Color::Gray
+                })
+                .fg(Color::Black),
+        );
+
+        let rows = area.height.saturating_sub(1) as usize;
+        // Scroll the dump so that the highlighted word stays visible.
+        let first = match self.highlight {
+            Some((line, _, _)) if line >= rows => line + 1 - rows,
+            _ => 0,
+        };
+        let fg = if self.selected {
+            Color::LightCyan
+        } else {
+            Color::Cyan
+        };
+        for (i, (base, words)) in self.lines.iter().skip(first).take(rows).enumerate() {
+            let y = area.top() + 1 + i as u16;
+            let prefix = format!("{:08x}: ", base);
+            buf.set_stringn(
+                area.left(),
+                y,
+                format!("{}{}", prefix, words),
+                area.width as usize,
+                Style::default().fg(fg),
+            );
+            if let Some((line, offset, len)) = self.highlight
+                && line == first + i
+            {
+                let x = prefix.len() + offset;
+                if x < area.width as usize {
+                    buf.set_stringn(
+                        area.left() + x as u16,
+                        y,
+                        &words[offset..offset + len],
+                        area.width as usize - x,
+                        Style::default().fg(Color::Black).bg(Color::Yellow),
+                    );
+                }
+            }
+        }
+    }
+}
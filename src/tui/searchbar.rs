@@ -3,7 +3,7 @@ use super::symbols::block;
 use crate::search::types::FindingsSummary;
 use tui::buffer::Buffer;
 use tui::layout::Rect;
-use tui::style::{Color, Style};
+use tui::style::{Color, Modifier, Style};
 use tui::symbols::line;
 use tui::widgets::Widget;
 
@@ -13,6 +13,7 @@ pub struct SearchBar<'a> {
     selected: bool,
     cursor: usize,
     visual_cursor: Option<usize>,
+    underline: Option<(usize, usize)>,
 }
 
 impl<'a> SearchBar<'a> {
@@ -29,9 +30,17 @@ impl<'a> SearchBar<'a> {
             selected,
             cursor,
             visual_cursor,
+            underline: None,
         }
     }
 
+    /// Underline `len` characters of the name, starting from `offset` (e.g. to point out a
+    /// syntax error).
+    pub fn underline(mut self, offset: usize, len: usize) -> SearchBar<'a> {
+        self.underline = Some((offset, len));
+        self
+    }
+
     fn findings_to_symbol(findings: &FindingsSummary) -> &'static str {
         match findings {
             FindingsSummary::Nothing => "·",
@@ -98,5 +107,14 @@ impl Widget for SearchBar<'_> {
                 })
                 .fg(Color::Black),
         );
+        if let Some((offset, len)) = self.underline {
+            let style = Style::default()
+                .fg(Color::Red)
+                .add_modifier(Modifier::UNDERLINED | Modifier::BOLD);
+            for x in offset..(offset + len).min(area.width as usize) {
+                buf.get_mut(area.left() + x as u16, area.top())
+                    .set_style(style);
+            }
+        }
     }
 }
@@ -120,7 +120,7 @@ impl Token {
     }
 
     fn retokenize_id_range(word: &str) -> Option<Token> {
-        for (i, c) in word.chars().enumerate() {
+        for (i, c) in word.char_indices() {
             if c == '[' {
                 if let Some(Token::Range(begin, end)) = Token::retokenize_range(&word[i..]) {
                     return Some(Token::IdentifierRange(word[..i].to_string(), begin, end));
@@ -255,6 +255,18 @@ mod test {
         assert_eq!(l.pop(Context::Stmt), Token::Eof);
     }
 
+    #[test]
+    fn id_range() {
+        let input = BufReader::new("données[7:0] µ".as_bytes());
+        let mut l = Lexer::new(input);
+        assert_eq!(
+            l.pop(Context::Id),
+            Token::IdentifierRange("données".to_string(), 7, 0)
+        );
+        assert_eq!(l.pop(Context::Id), Token::Identifier("µ".to_string()));
+        assert_eq!(l.pop(Context::Id), Token::Eof);
+    }
+
     #[test]
     fn empty_lines() {
         let input = BufReader::new("   \n$end".as_bytes());
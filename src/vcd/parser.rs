@@ -9,7 +9,16 @@ pub(crate) struct Parser<'a, I: BufRead> {
     lexer: Lexer<I>,
     signaldb: &'a SignalDB,
     scope: Vec<String>,
-    limit: Option<i64>,
+    limit: Option<Limit>,
+}
+
+/// Time after which the rest of the dump is ignored
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum Limit {
+    /// Number of ticks of the time scale of the dump
+    Ticks(i64),
+    /// Absolute time
+    Time(Timestamp),
 }
 
 #[derive(Debug, PartialEq)]
@@ -199,8 +208,8 @@ impl<'a, I: BufRead> Parser<'a, I> {
         }
     }
 
-    pub(crate) fn set_limit(&mut self, timestamp: i64) {
-        self.limit = Some(timestamp)
+    pub(crate) fn set_limit(&mut self, limit: Limit) {
+        self.limit = Some(limit)
     }
 
     pub(crate) fn parse(&mut self) -> Result<(), SyntaxError> {
@@ -228,10 +237,13 @@ impl<'a, I: BufRead> Parser<'a, I> {
                 Token::Timestamp(v) => {
                     let t = timescale * v;
                     self.signaldb.set_time(t);
-                    if let Some(limit) = self.limit {
-                        if v > limit {
-                            break Ok(());
-                        }
+                    let exceeded = match self.limit {
+                        Some(Limit::Ticks(limit)) => v > limit,
+                        Some(Limit::Time(limit)) => t > limit,
+                        None => false,
+                    };
+                    if exceeded {
+                        break Ok(());
                     }
                 }
                 Token::Value(v) => self.parse_value_change(v)?,
@@ -263,16 +275,16 @@ Any comment text.
 $end"
                 .as_bytes(),
         );
-        let mut db = SignalDB::new();
-        let mut p = Parser::new(input, &mut db);
+        let db = SignalDB::new();
+        let mut p = Parser::new(input, &db);
         assert_eq!(p.parse(), Ok(()))
     }
 
     #[test]
     fn fail() {
         let input = BufReader::new("$end".as_bytes());
-        let mut db = SignalDB::new();
-        let mut p = Parser::new(input, &mut db);
+        let db = SignalDB::new();
+        let mut p = Parser::new(input, &db);
         assert_eq!(
             p.parse(),
             Err(SyntaxError {